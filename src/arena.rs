@@ -0,0 +1,89 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use epic_not_chess_game::{elo_summary, random_action, win_notation, Color, GameState, Outcome, Player};
+use crate::cli::{Options, PlayerKind, PlayerSpec, Tournament};
+use crate::make_player;
+
+// How a game went for the first player named on the command line.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Score {
+    Win,
+    Loss,
+    Draw,
+}
+
+// The options a match player is made with: the shared ones, with its own depth or iterations.
+fn player_options(options: &Options, spec: PlayerSpec) -> Options {
+    let mut options = options.clone();
+    match (spec.kind, spec.setting) {
+        (PlayerKind::AlphaBeta, Some(depth)) => options.depth = depth,
+        (PlayerKind::Mcts, Some(iterations)) => {
+            options.iterations = iterations;
+            options.movetime = None;
+        },
+        _ => {},
+    }
+    options
+}
+
+// Plays one game to the end between `players[red]` as Red and the other as Blue, and returns the
+// winner, or None for a draw, with how many plies it took.
+fn play_game(state: &mut GameState, players: &mut [Box<dyn Player>; 2], red: usize, max_plies: u32) -> (Option<Color>, u32) {
+    let mut plies = 0;
+    loop {
+        match state.outcome() {
+            Some(Outcome::Win(winner, _)) => return (Some(winner), plies),
+            Some(Outcome::Draw(_)) => return (None, plies),
+            None => {},
+        }
+        if plies >= max_plies {
+            return (None, plies);
+        }
+        let player = &mut players[if state.turn() == Color::Blue { 1 - red } else { red }];
+        let action = player.choose_action(state);
+        state.apply(action);
+        plies += 1;
+    }
+}
+
+/// Plays the match, printing each game's result as it finishes and a summary at the end.
+pub fn run(options: &Options, tournament: &Tournament) {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let [first, second] = tournament.players;
+    println!("{} against {}, {} games. The seed for this match is {}.", first, second, tournament.games, seed);
+    let mut scores = Vec::new();
+    for game in 0..tournament.games {
+        // Both games of a pair start from the same opening, with the players swapping colours
+        let mut state = options.position.clone().unwrap_or_default();
+        state.set_no_progress_limit(options.no_progress_limit);
+        let mut opening_rng = StdRng::seed_from_u64(seed.wrapping_add(u64::from(game / 2)));
+        for _ in 0..tournament.random_plies {
+            match random_action(&state, &mut opening_rng) {
+                Some(action) if state.outcome().is_none() => state.apply(action),
+                _ => break,
+            }
+        }
+        let player_seed = seed.wrapping_add(2 * u64::from(game));
+        let mut players = [
+            make_player(first.kind, &player_options(options, first), player_seed),
+            make_player(second.kind, &player_options(options, second), player_seed.wrapping_add(1)),
+        ];
+        let first_is_red = game % 2 == 0;
+        let (winner, plies) = play_game(&mut state, &mut players, if first_is_red { 0 } else { 1 }, tournament.max_plies);
+        let (red_spec, blue_spec) = if first_is_red { (first, second) } else { (second, first) };
+        let result = match winner {
+            Some(winner) => win_notation(winner),
+            None => "1/2-1/2",
+        };
+        println!("Game {}: {} (Red) vs {} (Blue): {} after {} plies", game + 1, red_spec, blue_spec, result, plies);
+        scores.push(match winner {
+            None => Score::Draw,
+            Some(Color::Red) if first_is_red => Score::Win,
+            Some(Color::Blue) if !first_is_red => Score::Win,
+            Some(_) => Score::Loss,
+        });
+    }
+    let count = |score| scores.iter().filter(|&&s| s == score).count();
+    println!("\n{} against {}: {} wins, {} losses, {} draws", first, second, count(Score::Win), count(Score::Loss), count(Score::Draw));
+    println!("{}", elo_summary(count(Score::Win), count(Score::Draw), count(Score::Loss)));
+}
@@ -0,0 +1,30 @@
+use std::io::{self, BufRead, Write};
+use epic_not_chess_game::{Engine, Evaluator};
+
+// Speaks the engine protocol (see src/protocol.rs) on standard input and output.
+// Takes an optional evaluator weights file as its only argument.
+fn main() {
+    let evaluator = match std::env::args().nth(1) {
+        Some(path) => match Evaluator::load(&path) {
+            Ok(evaluator) => evaluator,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            },
+        },
+        None => Evaluator::default(),
+    };
+    let mut engine = Engine::new(evaluator);
+    let stdout = io::stdout();
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("Failed to read line");
+        let mut out = stdout.lock();
+        for reply in engine.handle(&line) {
+            writeln!(out, "{}", reply).expect("Failed to write reply");
+        }
+        out.flush().expect("Failed to write reply");
+        if engine.has_quit() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::piece::{Color, Kind, Piece, Square, BOARD_SIZE};
+use crate::rules::EXPLOSION_OFFSETS;
+
+// Sets of squares, one bit each, numbered rank * BOARD_SIZE + file.
+pub(crate) type Bitboard = u128;
+
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+
+pub(crate) const fn index(square: Square) -> usize {
+    square.rank * BOARD_SIZE + square.file
+}
+
+pub(crate) const fn bit(square: Square) -> Bitboard {
+    1 << index(square)
+}
+
+/// Every square in `board`, lowest first.
+pub(crate) fn squares(mut board: Bitboard) -> impl Iterator<Item = Square> {
+    std::iter::from_fn(move || {
+        if board == 0 {
+            return None;
+        }
+        let i = board.trailing_zeros() as usize;
+        board &= board - 1;
+        Some(Square {rank: i / BOARD_SIZE, file: i % BOARD_SIZE})
+    })
+}
+
+// The square `offset` away from square number `from`, if it's on the board.
+const fn offset_square(from: usize, offset: [i8; 2]) -> Option<usize> {
+    let rank = (from / BOARD_SIZE) as i32 + offset[0] as i32;
+    let file = (from % BOARD_SIZE) as i32 + offset[1] as i32;
+    if rank < 0 || file < 0 || rank >= BOARD_SIZE as i32 || file >= BOARD_SIZE as i32 {
+        None
+    } else {
+        Some(rank as usize * BOARD_SIZE + file as usize)
+    }
+}
+
+// For every square, the squares `offsets` away from it that are on the board.
+const fn offset_masks(offsets: &[[i8; 2]]) -> [Bitboard; SQUARES] {
+    let mut masks = [0; SQUARES];
+    let mut from = 0;
+    while from < SQUARES {
+        let mut i = 0;
+        while i < offsets.len() {
+            if let Some(to) = offset_square(from, offsets[i]) {
+                masks[from] |= 1 << to;
+            }
+            i += 1;
+        }
+        from += 1;
+    }
+    masks
+}
+
+// The squares a B stored on each square covers, or nothing if it would hang off the board.
+const fn big_footprints() -> [Bitboard; SQUARES] {
+    let mut masks = [0; SQUARES];
+    let mut from = 0;
+    while from < SQUARES {
+        if let Some(far_corner) = offset_square(from, [1, 1]) {
+            masks[from] = 1 << from | 1 << (from + 1) | 1 << (far_corner - 1) | 1 << far_corner;
+        }
+        from += 1;
+    }
+    masks
+}
+
+const HEALTH_5_STEPS: [[i8; 2]; 24] = [
+    [-2, -2], [-2, -1], [-2, 0], [-2, 1], [-2, 2],
+    [-1, -2], [-1, -1], [-1, 0], [-1, 1], [-1, 2],
+    [ 0, -2], [ 0, -1],          [ 0, 1], [ 0, 2],
+    [ 1, -2], [ 1, -1], [ 1, 0], [ 1, 1], [ 1, 2],
+    [ 2, -2], [ 2, -1], [ 2, 0], [ 2, 1], [ 2, 2],
+];
+const HEALTH_4_STEPS: [[i8; 2]; 12] = [[-2, -2], [-2, 2], [2, -2], [2, 2], [0, 2], [-2, 0], [0, -2], [2, 0], [-1, -1], [-1, 1], [1, 1], [1, -1]];
+const HEALTH_3_STEPS: [[i8; 2]; 8] = [[-1, -1], [-1, 0], [-1, 1], [0, 1], [1, 1], [1, 0], [1, -1], [0, -1]];
+const HEALTH_2_STEPS: [[i8; 2]; 4] = [[0, 1], [-1, 0], [0, -1], [1, 0]];
+const RED_HEALTH_1_STEPS: [[i8; 2]; 3] = [[0, 1], [0, -1], [1, 0]];
+const BLUE_HEALTH_1_STEPS: [[i8; 2]; 3] = [[0, 1], [-1, 0], [0, -1]];
+
+const HEALTH_5_MOVES: [Bitboard; SQUARES] = offset_masks(&HEALTH_5_STEPS);
+const HEALTH_4_MOVES: [Bitboard; SQUARES] = offset_masks(&HEALTH_4_STEPS);
+const HEALTH_3_MOVES: [Bitboard; SQUARES] = offset_masks(&HEALTH_3_STEPS);
+const HEALTH_2_MOVES: [Bitboard; SQUARES] = offset_masks(&HEALTH_2_STEPS);
+const RED_HEALTH_1_MOVES: [Bitboard; SQUARES] = offset_masks(&RED_HEALTH_1_STEPS);
+const BLUE_HEALTH_1_MOVES: [Bitboard; SQUARES] = offset_masks(&BLUE_HEALTH_1_STEPS);
+const NO_MOVES: [Bitboard; SQUARES] = [0; SQUARES];
+
+pub(crate) const BIG_FOOTPRINTS: [Bitboard; SQUARES] = big_footprints();
+// Where a B may step to, before checking its whole footprint fits there
+pub(crate) const BIG_MOVES: [Bitboard; SQUARES] = offset_masks(&HEALTH_2_STEPS);
+// The squares an exploding B damages
+pub(crate) const EXPLOSION_RINGS: [Bitboard; SQUARES] = offset_masks(&EXPLOSION_OFFSETS);
+
+/// The squares a Number piece may step to from each square, ignoring blockers.
+pub(crate) fn number_moves(color: Color, health: i8) -> &'static [Bitboard; SQUARES] {
+    match (health, color) {
+        (5, _) => &HEALTH_5_MOVES,
+        (4, _) => &HEALTH_4_MOVES,
+        (3, _) => &HEALTH_3_MOVES,
+        (2, _) => &HEALTH_2_MOVES,
+        (1, Color::Red) => &RED_HEALTH_1_MOVES,
+        (1, Color::Blue) => &BLUE_HEALTH_1_MOVES,
+        (1, Color::Green) => &HEALTH_2_MOVES, // Green has no forward, so its 1s go any orthogonal way
+        _ => &NO_MOVES,
+    }
+}
+
+/// The squares `piece` covers.
+pub(crate) fn footprint(piece: &Piece) -> Bitboard {
+    match piece.kind {
+        Kind::B => BIG_FOOTPRINTS[index(piece.pos)],
+        _ => bit(piece.pos),
+    }
+}
+
+/// Which squares each colour's pieces of each kind cover, kept up to date as pieces move and die.
+/// Pieces of the same colour never overlap, so a piece can be lifted off without disturbing the others.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub(crate) struct Occupancy {
+    boards: [[Bitboard; 3]; 3], // By colour, then kind
+}
+
+impl Occupancy {
+    pub(crate) fn new(pieces: &[Piece]) -> Occupancy {
+        let mut occupancy = Occupancy::default();
+        for piece in pieces {
+            occupancy.place(piece);
+        }
+        occupancy
+    }
+
+    pub(crate) fn place(&mut self, piece: &Piece) {
+        self.boards[piece.color as usize][piece.kind as usize] |= footprint(piece);
+    }
+
+    pub(crate) fn lift(&mut self, piece: &Piece) {
+        self.boards[piece.color as usize][piece.kind as usize] &= !footprint(piece);
+    }
+
+    pub(crate) fn of(&self, color: Color, kind: Kind) -> Bitboard {
+        self.boards[color as usize][kind as usize]
+    }
+
+    /// The squares `color`'s pieces stand in each other's way on: everything they cover but their goals.
+    pub(crate) fn blockers(&self, color: Color) -> Bitboard {
+        self.of(color, Kind::Number) | self.of(color, Kind::B)
+    }
+}
@@ -0,0 +1,241 @@
+use std::time::Duration;
+use epic_not_chess_game::{GameState, DEFAULT_NO_PROGRESS_LIMIT, MAX_DEPTH};
+
+pub const USAGE: &str = "\
+Usage: epic-not-chess-game [options]
+       epic-not-chess-game match <player> <player> [options]
+
+Options:
+  --human <red|blue|both|none>   Which side the human plays (default red)
+  --ai <random|alphabeta|mcts>   Which engine plays the other side (default alphabeta)
+  --red <kind>, --blue <kind>    Pick one side's player directly: human, random, alphabeta or mcts
+  --depth <n>                    How many plies alphabeta looks ahead (default 3, or as far as time
+                                 allows with --movetime)
+  --movetime <ms>                Give alphabeta and mcts this long per move instead
+  --threads <n>                  How many threads alphabeta searches on (default 1)
+  --iterations <n>               How many playouts mcts runs per move (default 500)
+  --position <notation>          Start from this position instead of the usual one
+  --no-progress-limit <n>        Draw the game after n plies without any damage; 0 turns this off
+                                 (default 100)
+  --seed <n>                     Seed for the engines' random choices, to replay a game exactly
+  --weights <file>               Evaluator weights to use instead of ./evaluator.cfg
+  --record <file>                Where to write the game record when the game ends (default last_game.txt)
+  --load <file>                  Carry on with a game saved with the save command
+  --replay <file>                Step through a recorded game instead of playing
+  --perft <depth>                Count the positions that many plies ahead, per first action, and exit
+  --analyse                      Search the position with alphabeta on one thread and on --threads
+                                 threads, compare the two, and exit
+  --no-color                     Don't use ANSI colours when drawing the board
+  --help                         Show this message
+
+Matches play engines against each other, swapping colours every game. A player is random,
+alphabeta or mcts, optionally followed by its depth or iterations, like alphabeta:4 or mcts:2000.
+The options above set everything else, and --seed makes a whole match repeatable.
+  --games <n>                    How many games to play (default 10)
+  --random-plies <n>             Open each pair of games with this many random plies (default 0)
+  --max-plies <n>                Call a game drawn after this many plies (default 300)
+
+While playing, type help at the move prompt for the commands you can use.";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerKind {
+    Human,
+    Random,
+    AlphaBeta,
+    Mcts,
+}
+
+fn parse_player_kind(kind: &str) -> Result<PlayerKind, String> {
+    match kind {
+        "human" => Ok(PlayerKind::Human),
+        "random" => Ok(PlayerKind::Random),
+        "alphabeta" => Ok(PlayerKind::AlphaBeta),
+        "mcts" => Ok(PlayerKind::Mcts),
+        _ => Err(format!("unknown player `{}`", kind)),
+    }
+}
+
+/// One side of a match: an engine, and its depth or iterations if they differ from the options.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlayerSpec {
+    pub kind: PlayerKind,
+    pub setting: Option<u32>,
+}
+
+fn parse_player_spec(spec: &str) -> Result<PlayerSpec, String> {
+    let (kind, setting) = match spec.split_once(':') {
+        Some((kind, setting)) => (kind, Some(parse_number(kind, setting)?)),
+        None => (spec, None),
+    };
+    let kind = parse_player_kind(kind)?;
+    if kind == PlayerKind::Human {
+        return Err("matches are between engines, so human can't play".to_string());
+    }
+    if setting == Some(0) {
+        return Err(format!("{}'s setting has to be at least 1", kind_name(kind)));
+    }
+    Ok(PlayerSpec {kind, setting})
+}
+
+fn kind_name(kind: PlayerKind) -> &'static str {
+    match kind {
+        PlayerKind::Human => "human",
+        PlayerKind::Random => "random",
+        PlayerKind::AlphaBeta => "alphabeta",
+        PlayerKind::Mcts => "mcts",
+    }
+}
+
+impl std::fmt::Display for PlayerSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.setting {
+            Some(setting) => write!(f, "{}:{}", kind_name(self.kind), setting),
+            None => write!(f, "{}", kind_name(self.kind)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    pub players: [PlayerSpec; 2],
+    pub games: u32,
+    pub random_plies: u32,
+    pub max_plies: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub red: PlayerKind,
+    pub blue: PlayerKind,
+    pub depth: u32,
+    pub iterations: u32,
+    pub movetime: Option<Duration>,
+    pub threads: usize,
+    pub position: Option<GameState>,
+    pub no_progress_limit: Option<u32>,
+    pub seed: Option<u64>,
+    pub weights: Option<String>,
+    pub record: String,
+    pub load: Option<String>,
+    pub replay: Option<String>,
+    pub perft: Option<u32>,
+    pub analyse: bool,
+    pub tournament: Option<Tournament>,
+    pub color: bool,
+    pub help: bool,
+}
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            red: PlayerKind::Human,
+            blue: PlayerKind::AlphaBeta,
+            depth: 3,
+            iterations: 500,
+            movetime: None,
+            threads: 1,
+            position: None,
+            no_progress_limit: Some(DEFAULT_NO_PROGRESS_LIMIT),
+            seed: None,
+            weights: None,
+            record: "last_game.txt".to_string(),
+            load: None,
+            replay: None,
+            perft: None,
+            analyse: false,
+            tournament: None,
+            color: true,
+            help: false,
+        }
+    }
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+    if args.peek().map(String::as_str) == Some("match") {
+        args.next();
+        let mut player = || args.next().ok_or_else(|| "match needs two players".to_string()).and_then(|spec| parse_player_spec(&spec));
+        let players = [player()?, player()?];
+        options.tournament = Some(Tournament {players, games: 10, random_plies: 0, max_plies: 300});
+    }
+    let mut human = None;
+    let mut ai = PlayerKind::AlphaBeta;
+    let (mut red, mut blue) = (None, None);
+    let mut depth = None;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--human" => human = Some(value("--human")?),
+            "--ai" => {
+                ai = parse_player_kind(&value("--ai")?)?;
+                if ai == PlayerKind::Human {
+                    return Err("--ai has to be an engine; use --human to pick the human's side".to_string());
+                }
+            },
+            "--red" => red = Some(parse_player_kind(&value("--red")?)?),
+            "--blue" => blue = Some(parse_player_kind(&value("--blue")?)?),
+            "--depth" => depth = Some(parse_number("--depth", &value("--depth")?)?),
+            "--iterations" => options.iterations = parse_number("--iterations", &value("--iterations")?)?,
+            "--movetime" => options.movetime = Some(Duration::from_millis(parse_number("--movetime", &value("--movetime")?)?)),
+            "--threads" => options.threads = parse_number("--threads", &value("--threads")?)?,
+            "--position" => {
+                let position = GameState::from_notation(&value("--position")?).map_err(|e| format!("--position: {}", e))?;
+                options.position = Some(position);
+            },
+            "--no-progress-limit" => {
+                let limit = parse_number("--no-progress-limit", &value("--no-progress-limit")?)?;
+                options.no_progress_limit = if limit == 0 { None } else { Some(limit) };
+            },
+            "--seed" => options.seed = Some(parse_number("--seed", &value("--seed")?)?),
+            "--weights" => options.weights = Some(value("--weights")?),
+            "--record" => options.record = value("--record")?,
+            "--load" => options.load = Some(value("--load")?),
+            "--replay" => options.replay = Some(value("--replay")?),
+            "--perft" => options.perft = Some(parse_number("--perft", &value("--perft")?)?),
+            "--analyse" | "--analyze" => options.analyse = true,
+            "--games" | "--random-plies" | "--max-plies" => {
+                let number = parse_number(&arg, &value(&arg)?)?;
+                let tournament = options.tournament.as_mut().ok_or(format!("{} only works with match", arg))?;
+                match arg.as_str() {
+                    "--games" => tournament.games = number,
+                    "--random-plies" => tournament.random_plies = number,
+                    _ => tournament.max_plies = number,
+                }
+            },
+            "--no-color" => options.color = false,
+            "--help" | "-h" => options.help = true,
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    let (red_is_human, blue_is_human) = match human.as_deref() {
+        None | Some("red") => (true, false),
+        Some("blue") => (false, true),
+        Some("both") => (true, true),
+        Some("none") => (false, false),
+        Some(side) => return Err(format!("--human takes red, blue, both or none, not `{}`", side)),
+    };
+    options.red = red.unwrap_or(if red_is_human { PlayerKind::Human } else { ai });
+    options.blue = blue.unwrap_or(if blue_is_human { PlayerKind::Human } else { ai });
+    options.depth = match (depth, options.movetime) {
+        (Some(depth), _) => depth,
+        (None, Some(_)) => MAX_DEPTH,
+        (None, None) => options.depth,
+    };
+    if options.depth == 0 {
+        return Err("--depth has to be at least 1".to_string());
+    }
+    if options.threads == 0 {
+        return Err("--threads has to be at least 1".to_string());
+    }
+    if options.iterations == 0 {
+        return Err("--iterations has to be at least 1".to_string());
+    }
+    if options.perft == Some(0) {
+        return Err("--perft has to be at least 1".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} takes a number, not `{}`", name, value))
+}
@@ -0,0 +1,26 @@
+// Elo estimates from match results, for telling whether one engine is really stronger than another.
+
+/// The Elo difference a match score suggests for the first player, with a 95% confidence interval
+/// from the spread of the results.
+pub fn elo_summary(wins: usize, draws: usize, losses: usize) -> String {
+    let games = wins + draws + losses;
+    if games == 0 {
+        return "No games were played.".to_string();
+    }
+    let n = games as f64;
+    let mean = (wins as f64 + 0.5 * draws as f64) / n;
+    // Adding zero turns -0 into 0 for printing
+    let elo = |score: f64| -400.0 * (1.0 / score - 1.0).log10() + 0.0;
+    if mean <= 0.0 || mean >= 1.0 {
+        return format!("Score {:.1}%, so the Elo difference is unbounded; play more games or closer opponents.", mean * 100.0);
+    }
+    // Every game a draw says nothing about how far the result could be from the truth
+    if wins + losses == 0 {
+        return format!("Score {:.1}%, Elo difference {:+.0} (every game was drawn, so there's no confidence interval; play more games)", mean * 100.0, elo(mean));
+    }
+    let variance = (wins as f64 * (1.0 - mean).powi(2) + draws as f64 * (0.5 - mean).powi(2) + losses as f64 * mean.powi(2)) / n;
+    let margin = 1.96 * (variance / n).sqrt();
+    let low = elo((mean - margin).max(1e-6));
+    let high = elo((mean + margin).min(1.0 - 1e-6));
+    format!("Score {:.1}%, Elo difference {:+.0} (95% confidence: {:+.0} to {:+.0})", mean * 100.0, elo(mean), low, high)
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use crate::game::GameState;
+use crate::bitboard::{index, Occupancy, EXPLOSION_RINGS};
+use crate::piece::{Color, Kind, Piece, BOARD_SIZE};
+use crate::rules::{count_possible_moves, goal_squares};
+
+/// How much each part of the evaluation counts for. All terms are in the same (arbitrary) units.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Weights {
+    pub number_health: i32, // Per point of health on a Number
+    pub big_health: i32, // Per point of health on a B
+    pub goal_distance: i32, // Per square closer to the nearest enemy goal
+    pub mobility: i32, // Per legal action more than the opponent has
+    pub explosion_threat: i32, // Per point of net enemy damage a B could deal by exploding
+}
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights {
+            number_health: 100,
+            big_health: 80,
+            goal_distance: 5,
+            mobility: 2,
+            explosion_threat: 30,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Syntax {line: usize, message: String},
+}
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "couldn't read evaluator config: {}", e),
+            ConfigError::Syntax {line, message} => write!(f, "evaluator config line {}: {}", line, message),
+        }
+    }
+}
+impl std::error::Error for ConfigError {}
+
+/// Scores positions for the search.
+///
+/// Weights can be loaded from a config file of `name = value` lines, where `name` is one of the
+/// fields of `Weights`. Blank lines and anything after a `#` are ignored, and missing weights keep
+/// their defaults.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Evaluator {
+    pub weights: Weights,
+}
+
+impl Evaluator {
+    pub fn new(weights: Weights) -> Evaluator {
+        Evaluator {weights}
+    }
+
+    pub fn from_config(config: &str) -> Result<Evaluator, ConfigError> {
+        let mut weights = Weights::default();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let syntax_error = |message: String| ConfigError::Syntax {line: number + 1, message};
+            let (name, value) = match line.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => return Err(syntax_error(format!("expected `name = value`, got `{}`", line))),
+            };
+            let value: i32 = value.parse().map_err(|_| syntax_error(format!("`{}` isn't a whole number", value)))?;
+            let weight = match name {
+                "number_health" => &mut weights.number_health,
+                "big_health" => &mut weights.big_health,
+                "goal_distance" => &mut weights.goal_distance,
+                "mobility" => &mut weights.mobility,
+                "explosion_threat" => &mut weights.explosion_threat,
+                _ => return Err(syntax_error(format!("unknown weight `{}`", name))),
+            };
+            *weight = value;
+        }
+        Ok(Evaluator {weights})
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Evaluator, ConfigError> {
+        Evaluator::from_config(&fs::read_to_string(path).map_err(ConfigError::Io)?)
+    }
+
+    /// The position from the point of view of the side to move. Positive is good for them.
+    pub fn evaluate(&self, state: &GameState) -> i32 {
+        let turn = state.turn();
+        let pieces = state.pieces();
+        let mut score = 0;
+        for piece in pieces {
+            if piece.kind == Kind::Goal || piece.color == Color::Green {
+                continue;
+            }
+            let value = match piece.kind {
+                Kind::Goal => 0,
+                Kind::Number => self.weights.number_health * piece.health as i32,
+                Kind::B => {
+                    self.weights.big_health * piece.health as i32 +
+                    self.weights.explosion_threat * explosion_threat(&state.occupancy, piece).max(0)
+                },
+            } + self.weights.goal_distance * (BOARD_SIZE as i32 - distance_to_goal(piece));
+            score += if piece.color == turn { value } else { -value };
+        }
+        if self.weights.mobility != 0 {
+            let occupancy = &state.occupancy;
+            let mobility = count_possible_moves(pieces, occupancy, turn) as i32 - count_possible_moves(pieces, occupancy, turn.opponent()) as i32;
+            score += self.weights.mobility * mobility;
+        }
+        score
+    }
+}
+
+// Chebyshev distance from any square the piece covers to the nearer of the goals it's going for.
+fn distance_to_goal(piece: &Piece) -> i32 {
+    let size = if piece.kind == Kind::B { 2 } else { 1 };
+    goal_squares(piece.color).iter().map(|goal| {
+        let axis_distance = |goal: usize, start: usize| {
+            if goal < start {
+                (start - goal) as i32
+            } else if goal >= start + size {
+                (goal - (start + size - 1)) as i32
+            } else {
+                0
+            }
+        };
+        axis_distance(goal.rank, piece.pos.rank).max(axis_distance(goal.file, piece.pos.file))
+    }).min().unwrap()
+}
+
+// Damage an explosion of `big` would do to enemies, minus what it would do to its own side.
+fn explosion_threat(occupancy: &Occupancy, big: &Piece) -> i32 {
+    let ring = EXPLOSION_RINGS[index(big.pos)];
+    let hits = |color| (occupancy.blockers(color) & ring).count_ones() as i32;
+    hits(big.color.opponent()) - hits(big.color)
+}
@@ -0,0 +1,234 @@
+use crate::bitboard::Occupancy;
+use crate::piece::{Action, Color, Kind, Piece, Square};
+use crate::resolve::{make_action, unmake_action, Undo};
+use crate::rules::{did_player_win, does_piece_block_square, find_possible_moves, has_possible_moves, list_possible_moves};
+use crate::zobrist;
+
+use std::fmt;
+
+/// The default for how many plies may pass without any piece taking damage before the game is drawn.
+pub const DEFAULT_NO_PROGRESS_LIMIT: u32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Win(Color, WinReason),
+    Draw(DrawReason),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WinReason {
+    Goals, // The winner covers both of the loser's goals
+    NoLegalActions, // The loser is to move and can't do anything
+}
+impl fmt::Display for WinReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            WinReason::Goals => "covering both goals",
+            WinReason::NoLegalActions => "no legal moves",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DrawReason {
+    Repetition, // The same position with the same side to move came up a third time
+    NoProgress, // Nothing took damage for the no-progress limit's worth of plies
+}
+impl fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            DrawReason::Repetition => "threefold repetition",
+            DrawReason::NoProgress => "no progress",
+        })
+    }
+}
+
+/// An owned position: every piece still on the board, whose turn it is, and how many plies have been played.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GameState {
+    pub(crate) pieces: Vec<Piece>,
+    pub(crate) occupancy: Occupancy, // Kept in step with `pieces` by make and unmake
+    pub(crate) hash: u64, // Likewise
+    pub(crate) turn: Color,
+    pub(crate) ply: u32,
+    pub(crate) quiet_plies: u32, // Plies since anything took damage
+    pub(crate) history: Vec<u64>, // Hashes of every earlier position, for spotting repetitions
+    pub(crate) no_progress_limit: Option<u32>,
+}
+
+impl Default for GameState {
+    fn default() -> GameState {
+        GameState::new()
+    }
+}
+
+impl GameState {
+    /// The standard starting position, with Red to move.
+    pub fn new() -> GameState {
+        let piece = |color, kind, health, rank, file| Piece {color, kind, health, pos: Square {rank, file}};
+        let pieces = vec![
+            piece(Color::Red,   Kind::Number, 1, 0, 0),
+            piece(Color::Red,   Kind::Number, 2, 0, 1),
+            piece(Color::Red,   Kind::Number, 3, 0, 2),
+            piece(Color::Red,   Kind::Number, 5, 0, 3),
+            piece(Color::Red,   Kind::Goal,   1, 0, 4),
+            piece(Color::Red,   Kind::Goal,   1, 0, 5),
+            piece(Color::Red,   Kind::Number, 5, 0, 6),
+            piece(Color::Red,   Kind::Number, 3, 0, 7),
+            piece(Color::Red,   Kind::Number, 2, 0, 8),
+            piece(Color::Red,   Kind::Number, 1, 0, 9),
+            piece(Color::Red,   Kind::Number, 3, 1, 0),
+            piece(Color::Red,   Kind::Number, 2, 1, 1),
+            piece(Color::Red,   Kind::Number, 2, 1, 2),
+            piece(Color::Red,   Kind::Number, 2, 1, 7),
+            piece(Color::Red,   Kind::Number, 2, 1, 8),
+            piece(Color::Red,   Kind::Number, 3, 1, 9),
+            piece(Color::Red,   Kind::B,      4, 2, 1),
+            piece(Color::Red,   Kind::B,      4, 2, 7),
+
+            piece(Color::Green, Kind::Number, 1, 4, 4),
+            piece(Color::Green, Kind::Number, 1, 4, 5),
+            piece(Color::Green, Kind::Number, 1, 5, 4),
+            piece(Color::Green, Kind::Number, 1, 5, 5),
+
+            piece(Color::Blue,  Kind::Number, 1, 9, 0),
+            piece(Color::Blue,  Kind::Number, 2, 9, 1),
+            piece(Color::Blue,  Kind::Number, 3, 9, 2),
+            piece(Color::Blue,  Kind::Number, 5, 9, 3),
+            piece(Color::Blue,  Kind::Goal,   1, 9, 4),
+            piece(Color::Blue,  Kind::Goal,   1, 9, 5),
+            piece(Color::Blue,  Kind::Number, 5, 9, 6),
+            piece(Color::Blue,  Kind::Number, 3, 9, 7),
+            piece(Color::Blue,  Kind::Number, 2, 9, 8),
+            piece(Color::Blue,  Kind::Number, 1, 9, 9),
+            piece(Color::Blue,  Kind::Number, 3, 8, 0),
+            piece(Color::Blue,  Kind::Number, 2, 8, 1),
+            piece(Color::Blue,  Kind::Number, 2, 8, 2),
+            piece(Color::Blue,  Kind::Number, 2, 8, 7),
+            piece(Color::Blue,  Kind::Number, 2, 8, 8),
+            piece(Color::Blue,  Kind::Number, 3, 8, 9),
+            piece(Color::Blue,  Kind::B,      4, 6, 1),
+            piece(Color::Blue,  Kind::B,      4, 6, 7),
+        ];
+        GameState::from_pieces(pieces, Color::Red)
+    }
+
+    pub fn from_pieces(pieces: Vec<Piece>, turn: Color) -> GameState {
+        GameState {
+            occupancy: Occupancy::new(&pieces),
+            hash: zobrist::hash(&pieces, turn),
+            pieces,
+            turn,
+            ply: 0,
+            quiet_plies: 0,
+            history: Vec::new(),
+            no_progress_limit: Some(DEFAULT_NO_PROGRESS_LIMIT),
+        }
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn turn(&self) -> Color {
+        self.turn
+    }
+
+    pub fn ply(&self) -> u32 {
+        self.ply
+    }
+
+    /// How many plies have gone by since any piece took damage.
+    pub fn quiet_plies(&self) -> u32 {
+        self.quiet_plies
+    }
+
+    pub fn no_progress_limit(&self) -> Option<u32> {
+        self.no_progress_limit
+    }
+
+    /// Sets how many plies without damage draw the game, or turns the rule off with None.
+    pub fn set_no_progress_limit(&mut self, limit: Option<u32>) {
+        self.no_progress_limit = limit;
+    }
+
+    /// How many times the current position has come up, counting this time. Only positions since
+    /// the last damage can match, since health never comes back.
+    pub fn repetitions(&self) -> usize {
+        let since_progress = self.history.len().saturating_sub(self.quiet_plies as usize);
+        1 + self.history[since_progress..].iter().filter(|&&hash| hash == self.hash).count()
+    }
+
+    /// A Zobrist hash of the pieces and the side to move. The ply count doesn't affect it.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The piece of the side to move on `square`, if any, counting every square a B covers.
+    /// Goals are never picked, since they can't move.
+    pub fn movable_piece_at(&self, square: Square) -> Option<&Piece> {
+        self.pieces.iter().find(|piece| piece.color == self.turn && piece.kind != Kind::Goal && does_piece_block_square(piece, square, false))
+    }
+
+    /// Whether the side to move can do anything at all. If not, they've lost.
+    pub fn has_legal_actions(&self) -> bool {
+        has_possible_moves(&self.pieces, &self.occupancy, self.turn)
+    }
+
+    /// Everything the side to move may do this turn.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        find_possible_moves(&self.pieces, &self.occupancy, self.turn)
+    }
+
+    /// Everything the side to move may do with the piece on `square`.
+    pub fn legal_actions_from(&self, square: Square) -> Vec<Action> {
+        match self.movable_piece_at(square) {
+            Some(piece) => list_possible_moves(&self.occupancy, piece),
+            None => Vec::new(),
+        }
+    }
+
+    /// How the game ended, if it has. A side wins by covering both enemy goals, Red's win being
+    /// checked first. Failing that, a side to move with no legal actions loses, and then the draw
+    /// rules apply. Green never wins; it's only ever in the way.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if did_player_win(&self.occupancy, Color::Red) {
+            Some(Outcome::Win(Color::Red, WinReason::Goals))
+        } else if did_player_win(&self.occupancy, Color::Blue) {
+            Some(Outcome::Win(Color::Blue, WinReason::Goals))
+        } else if !self.has_legal_actions() {
+            Some(Outcome::Win(self.turn.opponent(), WinReason::NoLegalActions))
+        } else if self.repetitions() >= 3 {
+            Some(Outcome::Draw(DrawReason::Repetition))
+        } else if self.no_progress_limit.is_some_and(|limit| self.quiet_plies >= limit) {
+            Some(Outcome::Draw(DrawReason::NoProgress))
+        } else {
+            None
+        }
+    }
+
+    /// Whether someone has won, ignoring the draw rules.
+    pub fn winner(&self) -> Option<Color> {
+        match self.outcome() {
+            Some(Outcome::Win(winner, _)) => Some(winner),
+            _ => None,
+        }
+    }
+
+    /// Plays `action` for the side to move, then Green's moves if that finished a round, and passes
+    /// the turn. `action` is expected to be legal.
+    pub fn apply(&mut self, action: Action) {
+        make_action(self, action);
+    }
+
+    /// Like `apply`, but returns a record that `unmake` can use to take the action back.
+    pub fn make(&mut self, action: Action) -> Undo {
+        make_action(self, action)
+    }
+
+    /// Restores the position from before the action `undo` was made for.
+    /// Undo records must be unmade in the reverse order they were made.
+    pub fn unmake(&mut self, undo: Undo) {
+        unmake_action(self, undo);
+    }
+}
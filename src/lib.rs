@@ -0,0 +1,32 @@
+mod bitboard;
+mod elo;
+mod eval;
+mod game;
+mod mcts;
+mod notation;
+mod perft;
+mod piece;
+mod player;
+mod protocol;
+mod record;
+mod render;
+mod resolve;
+mod rules;
+mod search;
+mod table;
+mod zobrist;
+
+pub use elo::elo_summary;
+pub use eval::{ConfigError, Evaluator, Weights};
+pub use game::{DrawReason, GameState, Outcome, WinReason, DEFAULT_NO_PROGRESS_LIMIT};
+pub use mcts::{random_action, Budget, Mcts};
+pub use notation::NotationError;
+pub use piece::{Action, Color, Kind, Move, Piece, Square, BOARD_SIZE};
+pub use perft::{divide, perft};
+pub use player::{Evaluation, Player, RandomPlayer, Suggestion};
+pub use protocol::{Engine, ENGINE_NAME};
+pub use record::{result_notation, win_notation, GameRecord, RecordError};
+pub use render::{render, Perspective};
+pub use resolve::{resolve_action, ActionReport, Damage, Undo};
+pub use search::{AlphaBeta, MAX_DEPTH, WIN_SCORE};
+pub use table::{Bound, TableEntry, TranspositionTable};
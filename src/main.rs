@@ -1,625 +1,334 @@
-use std::cmp;
-use std::fmt;
-use std::io;
-use rand::seq::SliceRandom;
+mod arena;
+mod cli;
+mod replay;
+mod shell;
 
-const TERMINAL_COLOR_GREEN:   &str = "\x1B[32m";
-const TERMINAL_COLOR_RED:     &str = "\x1B[31m";
-const TERMINAL_COLOR_BLUE:    &str = "\x1B[34m";
-const TERMINAL_COLOR_DEFAULT: &str = "\x1B[0m";
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use cli::{Options, PlayerKind};
+use shell::Command;
+use epic_not_chess_game::{divide, render, resolve_action, result_notation, win_notation, Action, AlphaBeta, Budget, Color, Evaluation, Evaluator, GameRecord, GameState, Mcts, Outcome, Perspective, Player, RandomPlayer, WinReason};
 
-#[derive(Clone, Copy, PartialEq)]
-enum Color { // TODO make this have Red and Blue more closely tied than Green somehow
-    Green,
-    Red,
-    Blue,
-}
-impl fmt::Display for Color {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", match self {
-            Color::Green => "Green",
-            Color::Red => "Red",
-            Color::Blue => "Blue",
-        })
-    }
-}
-#[derive(Clone, Copy)]
-enum Kind {
-    B, // Bigs are stored by their corner with the smallest coordinates (closest to a0)
-    Goal,
-    Number,
-}
-impl fmt::Display for Kind {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", match self {
-            Kind::B => "B",
-            Kind::Goal => "goal",
-            Kind::Number => "normal",
-        })
-    }
-}
-#[derive(Clone, Copy)]
-// It'd probably be easier to just keep track of the board state and not look at pieces' positions.
-struct Piece {
-    color: Color,
-    kind: Kind,
-    health: i8,
-    pos: Square,
-    delete: bool,
-}
-impl fmt::Display for Piece {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.kind {
-            Kind::Goal => write!(f, "Goal"),
-            _ => write!(f, "{} {} piece ({} health) on {}", self.color, self.kind, self.health, self.pos),
-        }
-    }
-}
-#[derive(Clone, Copy, PartialEq)]
-struct Square {
-    rank: usize,
-    file: usize,
-}
-impl fmt::Display for Square {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({}, {})", self.rank, self.file)
-    }
-}
-#[derive(Clone, Copy)]
-enum Action {
-    Move(Move),
-    Explosion(Square),
-}
-impl fmt::Display for Action {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", match self {
-            Action::Move(m) => format!("{}", m),
-            Action::Explosion(s) => format!("Explosion on {}", s),
-        })
-    }
-}
-#[derive(Clone, Copy)]
-struct Move {
-    start: Square,
-    end: Square,
-}
-impl fmt::Display for Move {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Move {} to {}", self.start, self.end)
-    }
-}
-#[derive(Clone, Copy)]
-struct RenderedPiece {
-    color: Color,
-    appearance: char,
-}
-const BOARD_SIZE: usize = 10;
-
-const NOT_A_RENDERED_PIECE: RenderedPiece = RenderedPiece {
-    color: Color::Green,
-    appearance: ' ',
-};
-fn render_pieces(pieces: &[&mut Piece]) {
-    let mut rendered_board = [[NOT_A_RENDERED_PIECE; BOARD_SIZE]; BOARD_SIZE];
-    for piece in pieces {
-        let rendered_rank = match HUMAN_PLAYER { // Render the board upside-down as Red
-            Color::Red => BOARD_SIZE - piece.pos.rank - 1,
-            Color::Blue => piece.pos.rank,
-            Color::Green => 0,
-        };
-        let rendered_file = match HUMAN_PLAYER { // And backward as Blue
-            Color::Red => piece.pos.file,
-            Color::Blue => BOARD_SIZE - piece.pos.file - 1,
-            Color::Green => 0,
-        };
-        rendered_board[rendered_rank][rendered_file].color = piece.color;
-        match piece.kind {
-            Kind::B => {
-                match HUMAN_PLAYER {
-                    Color::Red => { // Currently working on getting this to work. Make sure it's not L/R mirrored and then fix when human is Blue.
-                        rendered_board[rendered_rank - 1][rendered_file].appearance = '╔';
-                        rendered_board[rendered_rank - 1][rendered_file].color = piece.color;
-
-                        rendered_board[rendered_rank][rendered_file].appearance = '╚';
-
-                        rendered_board[rendered_rank - 1][rendered_file + 1].appearance = '╗';
-                        rendered_board[rendered_rank - 1][rendered_file + 1].color = piece.color;
+// Someone at this terminal, typing in their moves.
+struct HumanPlayer;
 
-                        rendered_board[rendered_rank][rendered_file + 1].appearance = '╝';
-                        rendered_board[rendered_rank][rendered_file + 1].color = piece.color;
-                    },
-                    Color::Blue => {
-                        //TODO this is wrong
-                        rendered_board[rendered_rank][rendered_file].appearance = '╔';
-                        rendered_board[rendered_rank + 1][rendered_file].appearance = '╚';
-                        rendered_board[rendered_rank + 1][rendered_file].color = piece.color;
-                        rendered_board[rendered_rank][rendered_file - 1].appearance = '╗';
-                        rendered_board[rendered_rank][rendered_file - 1].color = piece.color;
-                        rendered_board[rendered_rank][rendered_file + 1].appearance = '╝';
-                        rendered_board[rendered_rank][rendered_file + 1].color = piece.color;
-                    },
-                    Color::Green => (),
-                }
-            },
-            Kind::Goal => {
-                if rendered_board[rendered_rank][rendered_file].appearance == ' ' {
-                    // Don't render the goal over something else
-                    rendered_board[rendered_rank][rendered_file].appearance = 'X';
-                }
-            },
-            Kind::Number => {
-                rendered_board[rendered_rank][rendered_file].appearance = (b'0' + (piece.health as u8)) as char;
-            },
-        }
+impl Player for HumanPlayer {
+    fn name(&self) -> String {
+        "human".to_string()
     }
-    let mut counter = match HUMAN_PLAYER {
-        Color::Red => 9,
-        Color::Blue => 0,
-        Color::Green => 0,
-    };
-    for rendered_rank in &rendered_board {
-        print!("{} ", counter);
-        match HUMAN_PLAYER {
-            Color::Red   => counter -= 1,
-            Color::Blue  => counter += 1,
-            Color::Green => (),
-        }
-        for rendered_piece in rendered_rank {
-            print!("{}", match rendered_piece.color {
-                Color::Green => TERMINAL_COLOR_GREEN,
-                Color::Red   => TERMINAL_COLOR_RED,
-                Color::Blue  => TERMINAL_COLOR_BLUE,
-            });
-            print!("{}{}", rendered_piece.appearance, TERMINAL_COLOR_DEFAULT);
+
+    fn choose_action(&mut self, state: &GameState) -> Action {
+        loop {
+            match shell::read_command(state) {
+                Command::Act(action) => return action,
+                _ => println!("Only moves work here."),
+            }
         }
-        println!();
     }
-    println!("rf{}", match HUMAN_PLAYER {
-        Color::Red  => "0123456789",
-        Color::Blue => "9876543210",
-        Color::Green => "",
-    });
-}
 
-fn does_piece_block_square(potential_blocker: &Piece, square: Square, is_big: bool) -> bool {
-    if is_big {
-        // is_big: The square itself has a big hitbox. This is distinct from potential_blocker being a B.
-        does_piece_block_square(potential_blocker, square, false) ||
-        does_piece_block_square(potential_blocker, Square {rank: square.rank + 1, file: square.file}, false) ||
-        does_piece_block_square(potential_blocker, Square {rank: square.rank, file: square.file + 1}, false) ||
-        does_piece_block_square(potential_blocker, Square {rank: square.rank + 1, file: square.file + 1}, false)
-    } else {
-        match potential_blocker.kind {
-            Kind::B => {
-                (potential_blocker.pos == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank + 1,
-                    file: potential_blocker.pos.file,
-                } == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank,
-                    file: potential_blocker.pos.file + 1,
-                } == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank + 1,
-                    file: potential_blocker.pos.file + 1,
-                } == square)
-            },
-            Kind::Number => potential_blocker.pos == square,
-            Kind::Goal => false,
-        }
+    fn is_human(&self) -> bool {
+        true
     }
 }
 
-fn is_piece_blocking_square_from(potential_blocker: &Piece, from: &Piece, square: Square) -> bool {
-    if potential_blocker.color != from.color {
-        // Only pieces of your own color can block you.
-        return false;
-    }
-    match potential_blocker.kind {
-        Kind::B => {
-            if potential_blocker.pos == from.pos {
-                // B's shouldn't block themselves
-                false
-            } else {
-                (potential_blocker.pos == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank + 1,
-                    file: potential_blocker.pos.file,
-                } == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank,
-                    file: potential_blocker.pos.file + 1,
-                } == square) ||
-                (Square {
-                    rank: potential_blocker.pos.rank + 1,
-                    file: potential_blocker.pos.file + 1,
-                } == square)
-            }
+// Tuned weights are picked up from this file in the working directory, if there is one.
+const EVALUATOR_CONFIG: &str = "evaluator.cfg";
+fn load_evaluator(path: Option<&str>) -> Evaluator {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(EVALUATOR_CONFIG).exists() => EVALUATOR_CONFIG,
+        None => return Evaluator::default(),
+    };
+    match Evaluator::load(path) {
+        Ok(evaluator) => {
+            println!("Using the evaluator weights from {}.", path);
+            evaluator
+        },
+        Err(e) => {
+            println!("{}. Using the default weights instead.", e);
+            Evaluator::default()
         },
-        Kind::Number => potential_blocker.pos == square,
-        Kind::Goal => false,
     }
 }
 
-fn is_square_inhabitable_for(pieces: &[&mut Piece], piece: &Piece, square: Square) -> bool {
-    if square.rank >= BOARD_SIZE || square.file >= BOARD_SIZE {
-        return false;
-    } else {
-        for potential_blocker in pieces {
-            if is_piece_blocking_square_from(potential_blocker, piece, square) {
-                return false;
+fn make_player(kind: PlayerKind, options: &Options, seed: u64) -> Box<dyn Player> {
+    match kind {
+        PlayerKind::Human => Box::new(HumanPlayer),
+        PlayerKind::Random => Box::new(RandomPlayer::new(seed)),
+        PlayerKind::Mcts => {
+            let budget = options.movetime.map_or(Budget::Iterations(options.iterations), Budget::Time);
+            Box::new(Mcts::new(budget, seed).with_evaluator(load_evaluator(options.weights.as_deref())))
+        },
+        PlayerKind::AlphaBeta => {
+            let mut engine = AlphaBeta::new(options.depth).with_evaluator(load_evaluator(options.weights.as_deref()));
+            if let Some(limit) = options.movetime {
+                engine = engine.with_time_limit(limit);
             }
-        }
+            Box::new(engine.with_threads(options.threads))
+        },
     }
-    true
 }
 
-fn can_piece_move_to(pieces: &[&mut Piece], piece: &Piece, square: Square) -> bool {
-    match piece.kind {
-        Kind::B => {
-            is_square_inhabitable_for(pieces, piece, square) &&
-            is_square_inhabitable_for(pieces, piece, Square{rank: square.rank + 1, file: square.file}) &&
-            is_square_inhabitable_for(pieces, piece, Square{rank: square.rank, file: square.file + 1}) &&
-            is_square_inhabitable_for(pieces, piece, Square{rank: square.rank + 1, file: square.file + 1})
-        },
-        Kind::Number => is_square_inhabitable_for(pieces, piece, square),
-        Kind::Goal => false,
-    }
+// Hints come from the same kind of engine the other side is using, or alphabeta if that can't explain itself.
+fn hint_engine(options: &Options, side: Color) -> Box<dyn Player> {
+    let kind = match (side, options.red, options.blue) {
+        (Color::Blue, PlayerKind::Mcts, _) | (Color::Red, _, PlayerKind::Mcts) => PlayerKind::Mcts,
+        _ => PlayerKind::AlphaBeta,
+    };
+    make_player(kind, options, options.seed.unwrap_or(0))
 }
 
-fn add_usize_int(a: usize, b: i8) -> usize {
-    if a as i8 + b >= 0 {
-        (a as i8 + b) as usize
-    } else {
-        BOARD_SIZE // represents uninhabitable square
-    }
+// The position at the end of `record`, playing by the draw rules from the command line.
+fn current_position(record: &GameRecord, options: &Options) -> GameState {
+    let mut state = record.current_position();
+    state.set_no_progress_limit(options.no_progress_limit);
+    state
 }
 
-fn get_move_directions(piece: &Piece) -> Vec<[i8; 2]> {
-    match piece.kind {
-        Kind::B => vec![[1, 0], [-1, 0], [0, 1], [0, -1]],
-        Kind::Goal => Vec::new(),
-        Kind::Number => {
-            match piece.health {
-                5 => vec![
-                    [-2, -2], [-2, -1], [-2, 0], [-2, 1], [-2, 2],
-                    [-1, -2], [-1, -1], [-1, 0], [-1, 1], [-1, 2],
-                    [ 0, -2], [ 0, -1],          [ 0, 1], [ 0, 2],
-                    [ 1, -2], [ 1, -1], [ 1, 0], [ 1, 1], [ 1, 2],
-                    [ 2, -2], [ 2, -1], [ 2, 0], [ 2, 1], [ 2, 2],
-                ],
-                4 => vec![[-2, -2], [-2, 2], [2, -2], [2, 2], [0, 2], [-2, 0], [0, -2], [2, 0], [-1, -1], [-1, 1], [1, 1], [1, -1]],
-                3 => vec![[-1, -1], [-1, 0], [-1, 1], [0, 1], [1, 1], [1, 0], [1, -1], [0, -1]],
-                2 => vec![[0, 1], [-1, 0], [0, -1], [1, 0]],
-                1 => match piece.color {
-                    Color::Red => vec![[0, 1], [0, -1], [1, 0]],
-                    Color::Blue => vec![[0, 1], [-1, 0], [0, -1]],
-                    Color::Green => Vec::new(),
-                },
-                _ => Vec::new(),
-            }
+fn main() {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        },
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if let Some(depth) = options.perft {
+        let mut state = options.position.clone().unwrap_or_default();
+        let mut total = 0;
+        for (action, leaves) in divide(&mut state, depth) {
+            println!("{}: {}", action.to_notation(), leaves);
+            total += leaves;
         }
+        println!("\nperft({}) = {}", depth, total);
+        return;
     }
-}
-
-fn list_possible_moves(pieces: &[&mut Piece], piece: &Piece, moves: Vec<[i8; 2]>) -> Vec<Action> {
-    if let Kind::Goal = piece.kind {
-        return Vec::new();
+    if let Some(tournament) = &options.tournament {
+        arena::run(&options, tournament);
+        return;
+    }
+    if options.analyse {
+        analyse(&options);
+        return;
     }
-    let mut ret: Vec<Action> = moves.iter().map(|x| Move {
-        start: piece.pos,
-        end: Square {
-            rank: add_usize_int(piece.pos.rank, x[0]),
-            file: add_usize_int(piece.pos.file, x[1]),
+    if let Some(path) = &options.replay {
+        match GameRecord::load(path) {
+            Ok(record) => replay::replay(&record, options.color),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            },
         }
-    }).filter(|mv| can_piece_move_to(&pieces, piece, mv.end)).map(Action::Move).collect();
-    if let Kind::B = piece.kind {
-        ret.push(Action::Explosion(piece.pos));
+        return;
     }
-    ret
-}
-
-fn find_possible_moves(pieces: &[&mut Piece], turn: Color) -> Vec<Vec<Action>> {
-    pieces.iter().filter(|piece| piece.color == turn).map(|piece| 
-        list_possible_moves(pieces, piece, get_move_directions(piece))
-    ).collect()
-}
-
-fn ai_player_get_move(pieces: &[&mut Piece], ai_player: Color) -> Option<Action> {
-    find_possible_moves(pieces, ai_player).concat().choose(&mut rand::thread_rng()).copied()
-}
-
-fn did_player_win(pieces: &[&mut Piece], player: Color) -> bool {
-    let goal1;
-    let goal2;
-    match player {
-        Color::Blue => {
-            // Positions of Red's goals
-            goal1 = Square{rank: 0, file: 4};
-            goal2 = Square{rank: 0, file: 5};
-        },
-        _ => {
-            // Positions of Blue's goals
-            goal1 = Square{rank: 9, file: 4};
-            goal2 = Square{rank: 9, file: 5};
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut red = make_player(options.red, &options, seed);
+    let mut blue = make_player(options.blue, &options, seed.wrapping_add(1));
+    let mut record = match &options.load {
+        Some(path) => match GameRecord::load(path) {
+            Ok(record) => {
+                println!("Resuming the game saved in {}.", path);
+                record
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            },
         },
+        None => GameRecord::new(options.position.clone().unwrap_or_default()),
+    };
+    let mut state = current_position(&record, &options);
+    record.set_header("Red", &red.name());
+    record.set_header("Blue", &blue.name());
+    record.set_header("Seed", &seed.to_string());
+    if record.header("Date").is_none() {
+        record.set_header("Date", &today());
     }
-    let mut goal1_reached = false;
-    let mut goal2_reached = false;
-    for piece in pieces {
-        if player == piece.color {
-            // Only your own pieces count toward your win
-            if does_piece_block_square(piece, goal1, false) {
-                goal1_reached = true;
-            }
-            if does_piece_block_square(piece, goal2, false) {
-                goal2_reached = true;
-            }
+    println!("Welcome to this game. {} is playing as Red, and {} is playing as Blue.", red.name(), blue.name());
+    println!("The seed for this game is {}.", seed);
+    println!("Green belongs to nobody. After each of Blue's turns, every Green piece steps toward the nearest Red or Blue piece and fights it on contact.");
+    let mut flips = 0;
+    let mut resigned = false;
+    let mut quit = false;
+    loop {
+        // Show the board the right way up for whoever is at the terminal, preferring the side to move
+        let mut perspective = if red.is_human() && (state.turn() == Color::Red || !blue.is_human()) {
+            Perspective::Red
+        } else if blue.is_human() {
+            Perspective::Blue
+        } else {
+            Perspective::Spectator
+        };
+        for _ in 0..flips {
+            perspective = perspective.flipped();
         }
-    }
-    goal1_reached && goal2_reached
-}
-
-const HUMAN_PLAYER: Color = Color::Red;
-fn main() {
-    let mut curr_game_turn = Color::Red;
-    //let curr_tic_tac_toe_turn = Color::Blue;
-    let mut all_pieces: Vec<Piece> = vec![
-        Piece {color: Color::Red,   kind: Kind::Number, health: 1, pos: Square {rank: 0, file: 0}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 0, file: 1}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 3, pos: Square {rank: 0, file: 2}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 5, pos: Square {rank: 0, file: 3}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Goal,   health: 1, pos: Square {rank: 0, file: 4}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Goal,   health: 1, pos: Square {rank: 0, file: 5}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 5, pos: Square {rank: 0, file: 6}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 3, pos: Square {rank: 0, file: 7}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 0, file: 8}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 1, pos: Square {rank: 0, file: 9}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 3, pos: Square {rank: 1, file: 0}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 1, file: 1}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 1, file: 2}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 1, file: 7}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 2, pos: Square {rank: 1, file: 8}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::Number, health: 3, pos: Square {rank: 1, file: 9}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::B,      health: 4, pos: Square {rank: 2, file: 1}, delete: false},
-        Piece {color: Color::Red,   kind: Kind::B,      health: 4, pos: Square {rank: 2, file: 7}, delete: false},
-    
-        Piece {color: Color::Green, kind: Kind::Number, health: 1, pos: Square {rank: 4, file: 4}, delete: false},
-        Piece {color: Color::Green, kind: Kind::Number, health: 1, pos: Square {rank: 4, file: 5}, delete: false},
-        Piece {color: Color::Green, kind: Kind::Number, health: 1, pos: Square {rank: 5, file: 4}, delete: false},
-        Piece {color: Color::Green, kind: Kind::Number, health: 1, pos: Square {rank: 5, file: 5}, delete: false},
-    
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 1, pos: Square {rank: 9, file: 0}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 9, file: 1}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 3, pos: Square {rank: 9, file: 2}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 5, pos: Square {rank: 9, file: 3}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Goal,   health: 1, pos: Square {rank: 9, file: 4}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Goal,   health: 1, pos: Square {rank: 9, file: 5}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 5, pos: Square {rank: 9, file: 6}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 3, pos: Square {rank: 9, file: 7}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 9, file: 8}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 1, pos: Square {rank: 9, file: 9}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 3, pos: Square {rank: 8, file: 0}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 8, file: 1}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 8, file: 2}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 8, file: 7}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 2, pos: Square {rank: 8, file: 8}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::Number, health: 3, pos: Square {rank: 8, file: 9}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::B,      health: 4, pos: Square {rank: 6, file: 1}, delete: false},
-        Piece {color: Color::Blue,  kind: Kind::B,      health: 4, pos: Square {rank: 6, file: 7}, delete: false},
-    ];
-    let mut pieces = all_pieces.iter_mut().collect::<Vec<&mut Piece>>();
-    println!("Welcome to this game. You, the human, are playing as {}.", HUMAN_PLAYER);
-    'main_loop: loop {
-        // Delete all pieces marked for deletion
-        pieces = pieces.into_iter().filter(|piece| !piece.delete).collect();
 
         //Figure out if anybody won
-        let red_won = did_player_win(&pieces, Color::Red);
-        let blue_won = did_player_win(&pieces, Color::Blue);
-        if red_won {
-            println!("Red won!");
-            render_pieces(&pieces);
-            break;
+        match state.outcome() {
+            Some(Outcome::Win(winner, WinReason::NoLegalActions)) => {
+                println!("{} has no legal moves, so {} wins!", winner.opponent(), winner);
+            },
+            Some(Outcome::Win(winner, WinReason::Goals)) => println!("{} won!", winner),
+            Some(Outcome::Draw(reason)) => println!("The game is drawn by {}.", reason),
+            None => {},
         }
-        if blue_won {
-            println!("Blue won!");
-            render_pieces(&pieces);
+        if state.outcome().is_some() {
+            println!("{}", render(state.pieces(), perspective, options.color));
             break;
         }
-        render_pieces(&pieces);
+        println!("{}", render(state.pieces(), perspective, options.color));
 
-        //println!("{:?}", find_possible_moves(&pieces, curr_game_turn));
-        if curr_game_turn == HUMAN_PLAYER {
-            println!("It's your turn ({})!", curr_game_turn);
-            if find_possible_moves(&pieces, curr_game_turn).is_empty() {
-                println!("You have no legal moves! Your opponent wins!");
-                break 'main_loop;
-            }
-            'get_player_input: loop {
-                println!("Move piece at which rank? ");
-                let mut rank_moved = String::new();
-                io::stdin().read_line(&mut rank_moved).expect("Failed to read line");
-                let rank_moved: usize = match rank_moved.trim().parse() {
-                    Ok(num) => num,
-                    Err(_) => continue,
-                };
-                println!("At which file? ");
-                let mut file_moved = String::new();
-                io::stdin().read_line(&mut file_moved).expect("Failed to read line");
-                let file_moved: usize = match file_moved.trim().parse() {
-                    Ok(num) => num,
-                    Err(_) => continue,
-                };
-                for index in 0..pieces.len() {
-                    let piece = &pieces[index];
-                    if piece.pos == (Square{rank: rank_moved, file: file_moved}) {
-                        println!("Choosing to move {}.", piece);
-                        if piece.color != curr_game_turn {
-                            println!("That piece isn't yours to move!");
-                            continue 'get_player_input;
-                        }
-                        let possible_moves = list_possible_moves(&pieces, piece, get_move_directions(piece));
-                        let move_made: &Action;
-                        match possible_moves.len() {
-                            0 => {
-                                println!("This piece has no legal moves!");
-                                continue 'get_player_input;
-                            }
-                            1 => {
-                                move_made = possible_moves.get(0).unwrap();
-                                println!("Piece has one single legal move: {}. Undergoing that move", move_made);
-                            },
-                            _ => {
-                                loop {
-                                    println!("Which move of {}?", possible_moves.iter().fold(String::new(), |a, &m| a + &m.to_string() + ", "));
-                                    let mut index_chosen = String::new();
-                                    io::stdin().read_line(&mut index_chosen).expect("Failed to read line");
-                                    let index_chosen: usize = match index_chosen.trim().parse() {
-                                        Ok(num) => num,
-                                        Err(_) => continue,
-                                    };
-                                    
-                                    match possible_moves.get(index_chosen) {
-                                        Some(i) => {
-                                            move_made = i;
-                                            println!("Making move {}", i);
-                                            break;
-                                        },
-                                        None => {
-                                            println!("Index {} isn't a possible move; there were only {}!", index_chosen, possible_moves.len());
-                                            continue;
-                                        },
-                                    }
-                                }
+        let player = match state.turn() {
+            Color::Blue => &mut blue,
+            _ => &mut red,
+        };
+        println!("It's {}'s turn ({}).", state.turn(), player.name());
+        let action = if player.is_human() {
+            match shell::read_command(&state) {
+                Command::Act(action) => action,
+                Command::Undo => {
+                    // Take back the human's last action along with any engine replies since
+                    let human_to_move = |count: usize| match record.position_after(count).turn() {
+                        Color::Blue => blue.is_human(),
+                        _ => red.is_human(),
+                    };
+                    match (0..record.actions().len()).rev().find(|&count| human_to_move(count)) {
+                        Some(count) => {
+                            let taken_back = record.actions().len() - count;
+                            while record.actions().len() > count {
+                                record.pop();
                             }
-                        }
-                        // Actually make the move
-
-                        match move_made {
-                            Action::Explosion(sq) => {
-                                for offset in &[[-1, -1], [0, -1], [1, -1], [2, -1], [2, 0], [2, 1], [2, 2], [1, 2], [0, 2], [-1, 2], [-1, 1], [-1, 0]] {
-                                    for damaged_piece in &mut pieces {
-                                        if does_piece_block_square(damaged_piece, Square {rank: add_usize_int(sq.rank, offset[0]), file: add_usize_int(sq.file ,offset[1])}, false) {
-                                            damaged_piece.health -= 1;
-                                            if damaged_piece.health <= 0 {
-                                                damaged_piece.delete = true;
-                                            }
-                                        }
-                                    }
-                                }
-                                // add ones
-                                /*for offset in &[[-1, -1], [2, -1], [2, 2], [-1, 2]] {
-                                    let spawned_pos = Square {rank: add_usize_int(piece.pos.rank, offset[0]), file: add_usize_int(piece.pos.file, offset[1])};
-                                    if is_square_inhabitable_for(&pieces, piece, spawned_pos) {
-                                        let mut one = Piece {color: curr_game_turn, kind: Kind::Number, health: 1, pos: spawned_pos, delete: false};
-                                    }
-                                }*/
-                                // Delete the big that exploded
-                                let piece = &mut pieces[index];
-                                piece.delete = true;
-                                break 'get_player_input;
-                            },
-                            Action::Move(mv) => {
-                                let big = matches!(pieces[index].kind, Kind::B);
-                                let mut damage = pieces[index].health;
-                                let mut total_damage = 0;
-                                for attacked_piece in &mut pieces {
-                                    if attacked_piece.color != curr_game_turn && does_piece_block_square(attacked_piece, mv.end, big) {
-                                        damage = cmp::min(damage, attacked_piece.health);
-                                        attacked_piece.health -= damage;
-                                        total_damage += damage;
-                                        if attacked_piece.health <= 0 {
-                                            attacked_piece.delete = true;
-                                        }
-                                    }
-                                }
-                                let piece = &mut pieces[index];
-                                piece.health -= total_damage;
-                                if piece.health <= 0 {
-                                    piece.delete = true;
-                                }
-                                piece.pos = mv.end;
-                                break 'get_player_input;
-                            },
-                        }
-
+                            state = current_position(&record, &options);
+                            println!("Took back {} action{}.", taken_back, if taken_back == 1 { "" } else { "s" });
+                        },
+                        None => println!("There's nothing to undo."),
                     }
-                }
-                // TODO if you input an index that isn't a piece, it will just loop again, which is weird
-            }
-        } else { // AI player's turn
-            println!("It's the AI player's turn now ({}).", curr_game_turn);
-            // Actually make the move
-            // TODO this code is duplicated
-            let move_made = ai_player_get_move(&pieces, curr_game_turn);
-            match move_made {
-                Some(action) => {
-                    println!("The AI player made move {}", action);
-                    for index in 0..pieces.len() {
-                        if pieces[index].pos == match action {
-                            Action::Explosion(sq) => sq,
-                            Action::Move(m) => m.start,
-                        } {
-                            match action {
-                                Action::Explosion(sq) => {
-                                    let piece = &mut pieces[index];
-                                    // Delete the big that exploded
-                                    piece.delete = true;
-                                    if piece.pos == sq {
-                                        for offset in &[[-1, -1], [0, -1], [1, -1], [2, -1], [2, 0], [2, 1], [2, 2], [1, 2], [0, 2], [-1, 2], [-1, 1], [-1, 0]] {
-                                            for damaged_piece in &mut pieces {
-                                                if does_piece_block_square(damaged_piece, Square {rank: add_usize_int(sq.rank, offset[0]), file: add_usize_int(sq.file ,offset[1])}, false) {
-                                                    damaged_piece.health -= 1;
-                                                    if damaged_piece.health <= 0 {
-                                                        damaged_piece.delete = true;
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                },
-                                Action::Move(mv) => {
-                                    let big = matches!(pieces[index].kind, Kind::B);
-                                    if pieces[index].pos == mv.start {
-                                        let mut damage = pieces[index].health;
-                                        let mut total_damage = 0;
-                                        for attacked_piece in &mut pieces {
-                                            if attacked_piece.color != curr_game_turn && does_piece_block_square(attacked_piece, mv.end, big) {
-                                                damage = cmp::min(damage, attacked_piece.health);
-                                                attacked_piece.health -= damage;
-                                                total_damage += damage;
-                                                if attacked_piece.health <= 0 {
-                                                    attacked_piece.delete = true;
-                                                }
-                                            }
-                                        }
-                                        let piece = &mut pieces[index];
-                                        piece.health -= total_damage;
-                                        if piece.health <= 0 {
-                                            piece.delete = true;
-                                        }
-                                        piece.pos = mv.end;
-                                        break;
-                                    }
-                                }
-                            }
-                            break;
-                        }
+                    continue;
+                },
+                Command::Hint => {
+                    let mut engine = hint_engine(&options, state.turn());
+                    println!("Asking {} for a hint...", engine.name());
+                    match engine.suggest(&state) {
+                        Some(hint) => println!("Try {} ({}). Evaluation: {}.", hint.action.to_notation(), hint.action, hint.evaluation),
+                        None => println!("There's nothing to suggest."),
+                    }
+                    continue;
+                },
+                Command::Resign => {
+                    println!("{} resigns, so {} wins!", state.turn(), state.turn().opponent());
+                    resigned = true;
+                    break;
+                },
+                Command::Save(path) => {
+                    match record.save(&path) {
+                        Ok(()) => println!("Saved the game to {}. Start with --load {} to carry on later.", path, path),
+                        Err(e) => println!("{}", e),
+                    }
+                    continue;
+                },
+                Command::Load(path) => {
+                    match GameRecord::load(&path) {
+                        Ok(loaded) => {
+                            println!("Loaded the game saved in {}.", path);
+                            record = loaded;
+                            record.set_header("Red", &red.name());
+                            record.set_header("Blue", &blue.name());
+                            state = current_position(&record, &options);
+                        },
+                        Err(e) => println!("{}", e),
                     }
+                    continue;
+                },
+                Command::Flip => {
+                    flips = (flips + 1) % 3;
+                    continue;
                 },
-                None => {
-                    println!("The enemy player has no legal moves, so you win!");
+                Command::Quit => {
+                    quit = true;
+                    break;
                 },
             }
-        }
-    
-        // End of main loop. Switch the current turn.
-        curr_game_turn = match curr_game_turn {
-            Color::Red => Color::Blue,
-            Color::Blue => Color::Red,
-            Color::Green => panic!("It's Green's turn somehow"),
+        } else {
+            let action = player.choose_action(&state);
+            if let Some(report) = player.search_report() {
+                println!("{}", report);
+            }
+            action
         };
+        println!("{}", resolve_action(&mut state, action));
+        record.push(action);
     }
+    println!("Final position: {}", state.to_notation());
+    if quit {
+        record.set_header("Result", result_notation(None));
+    } else if resigned {
+        record.set_header("Result", win_notation(state.turn().opponent()));
+        record.set_header("Termination", "resignation");
+    } else {
+        record.set_header("Result", result_notation(state.outcome()));
+        match state.outcome() {
+            Some(Outcome::Draw(reason)) => record.set_header("Termination", &reason.to_string()),
+            Some(Outcome::Win(_, reason @ WinReason::NoLegalActions)) => record.set_header("Termination", &reason.to_string()),
+            _ => {},
+        }
+    }
+    match record.save(&options.record) {
+        Ok(()) => println!("The game record was written to {}.", options.record),
+        Err(e) => println!("{}", e),
+    }
+}
+
+// Searches one position on a single thread and then on all of them, to see what the extra threads buy.
+fn analyse(options: &Options) {
+    let state = options.position.clone().unwrap_or_default();
+    let evaluator = load_evaluator(options.weights.as_deref());
+    let mut timings = Vec::new();
+    for threads in [1, options.threads] {
+        let mut engine = AlphaBeta::new(options.depth).with_evaluator(evaluator).with_threads(threads);
+        if let Some(limit) = options.movetime {
+            engine = engine.with_time_limit(limit);
+        }
+        let start = Instant::now();
+        let best = engine.best_action(&state);
+        let seconds = start.elapsed().as_secs_f64();
+        let nodes_per_second = engine.nodes() as f64 / seconds.max(1e-9);
+        match best {
+            Some((action, score)) => println!("{} thread{}: depth {}, best {} ({}), {} nodes in {:.2} s, {:.0} nodes/s",
+                threads, if threads == 1 { "" } else { "s" }, engine.completed_depth(), action.to_notation(),
+                Evaluation::Score(score), engine.nodes(), seconds, nodes_per_second),
+            None => {
+                println!("There are no legal actions to search.");
+                return;
+            },
+        }
+        timings.push((seconds, nodes_per_second, engine.completed_depth()));
+    }
+    if let [(one_time, one_speed, one_depth), (all_time, all_speed, all_depth)] = timings[..] {
+        if options.movetime.is_some() {
+            println!("With the same time, {} threads reached depth {} against {}.", options.threads, all_depth, one_depth);
+        } else {
+            println!("Speedup: {:.2}x faster to depth {}", one_time / all_time.max(1e-9), all_depth);
+        }
+        println!("Node rate: {:.2}x", all_speed / one_speed.max(1e-9));
+    }
+}
+
+// Today's date in UTC as YYYY.MM.DD, like PGN uses.
+fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    // Days since 1970-01-01 to a civil date, after Howard Hinnant's civil_from_days algorithm
+    let days = (seconds / 86400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}.{:02}.{:02}", year, month, day)
 }
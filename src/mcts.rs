@@ -0,0 +1,186 @@
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use crate::eval::Evaluator;
+use crate::game::{GameState, Outcome};
+use crate::piece::{Action, Color};
+
+const EXPLORATION: f64 = 1.4;
+// Random games rarely reach a goal, so playouts stop after this many plies and are scored by the evaluator instead
+const MAX_PLAYOUT_PLIES: u32 = 40;
+// Evaluator score that counts as roughly a 73% chance of winning
+const EVALUATION_SCALE: f64 = 300.0;
+
+/// Picks uniformly among the side to move's legal actions. This is also the MCTS rollout policy.
+pub fn random_action<R: Rng + ?Sized>(state: &GameState, rng: &mut R) -> Option<Action> {
+    state.legal_actions().choose(rng).copied()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+struct Node {
+    action: Option<Action>,
+    mover: Color, // Who played `action` to get here
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Action>,
+    visits: u32,
+    score: f64, // Total playout results from `mover`'s point of view, 1 for a win and 0 for a loss
+}
+
+/// Monte Carlo tree search with uniformly random playouts.
+/// Playouts that don't finish within a few dozen plies are scored by an `Evaluator`.
+#[derive(Clone, Debug)]
+pub struct Mcts {
+    budget: Budget,
+    evaluator: Evaluator,
+    rng: StdRng,
+    iterations: u32,
+}
+
+impl Mcts {
+    /// The same seed and budget always give the same choices, unless the budget is a time limit.
+    pub fn new(budget: Budget, seed: u64) -> Mcts {
+        Mcts {budget, evaluator: Evaluator::default(), rng: StdRng::seed_from_u64(seed), iterations: 0}
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> Mcts {
+        self.evaluator = evaluator;
+        self
+    }
+
+    pub fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    /// How many playouts the last search ran.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    /// The most visited action for the side to move, or None if it has no legal actions.
+    pub fn best_action(&mut self, state: &GameState) -> Option<Action> {
+        let root_actions = state.legal_actions();
+        if root_actions.len() <= 1 {
+            self.iterations = 0;
+            return root_actions.first().copied();
+        }
+        self.analyse(state).map(|(action, _)| action)
+    }
+
+    /// Like `best_action`, but always searches and also returns the side to move's estimated chance
+    /// of winning after that action, from 0 to 1.
+    pub fn analyse(&mut self, state: &GameState) -> Option<(Action, f64)> {
+        let root_actions = state.legal_actions();
+        if root_actions.is_empty() {
+            self.iterations = 0;
+            return None;
+        }
+        let mut tree = vec![Node {
+            action: None,
+            mover: state.turn().opponent(),
+            parent: None,
+            children: Vec::new(),
+            untried: root_actions,
+            visits: 0,
+            score: 0.0,
+        }];
+        let start = Instant::now();
+        self.iterations = 0;
+        // At least one playout, so the root has a child to pick however small the budget
+        loop {
+            let done = self.iterations > 0 && match self.budget {
+                Budget::Iterations(iterations) => self.iterations >= iterations,
+                Budget::Time(limit) => start.elapsed() >= limit,
+            };
+            if done {
+                break;
+            }
+            self.iterate(&mut tree, state);
+            self.iterations += 1;
+        }
+        let best = *tree[0].children.iter().max_by_key(|&&child| tree[child].visits)?;
+        let node = &tree[best];
+        Some((node.action?, node.score / node.visits.max(1) as f64))
+    }
+
+    fn iterate(&mut self, tree: &mut Vec<Node>, root: &GameState) {
+        let mut state = root.clone();
+        // Selection: walk down fully expanded nodes by UCT
+        let mut node = 0;
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let parent_visits = (tree[node].visits as f64).ln();
+            node = *tree[node].children.iter().max_by(|&&a, &&b| {
+                uct(&tree[a], parent_visits).partial_cmp(&uct(&tree[b], parent_visits)).unwrap()
+            }).unwrap();
+            state.apply(tree[node].action.unwrap());
+        }
+        // Expansion: add one untried action, unless the game is already over here
+        if state.outcome().is_none() && !tree[node].untried.is_empty() {
+            let index = self.rng.gen_range(0..tree[node].untried.len());
+            let action = tree[node].untried.swap_remove(index);
+            let mover = state.turn();
+            state.apply(action);
+            tree.push(Node {
+                action: Some(action),
+                mover,
+                parent: Some(node),
+                children: Vec::new(),
+                untried: if state.outcome().is_none() { state.legal_actions() } else { Vec::new() },
+                visits: 0,
+                score: 0.0,
+            });
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            node = child;
+        }
+        // Simulation
+        let red_result = self.playout(&mut state);
+        // Backpropagation
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.score += if node.mover == Color::Red { red_result } else { 1.0 - red_result };
+            current = node.parent;
+        }
+    }
+
+    // Plays randomly from `state` and returns how well it went for Red, between 0 and 1.
+    fn playout(&mut self, state: &mut GameState) -> f64 {
+        for _ in 0..MAX_PLAYOUT_PLIES {
+            if state.outcome().is_some() {
+                break;
+            }
+            match random_action(state, &mut self.rng) {
+                Some(action) => state.apply(action),
+                None => break,
+            }
+        }
+        match state.outcome() {
+            Some(Outcome::Win(Color::Red, _)) => 1.0,
+            Some(Outcome::Win(..)) => 0.0,
+            Some(Outcome::Draw(_)) => 0.5,
+            None => {
+                let mut score = self.evaluator.evaluate(state) as f64;
+                if state.turn() != Color::Red {
+                    score = -score;
+                }
+                1.0 / (1.0 + (-score / EVALUATION_SCALE).exp())
+            },
+        }
+    }
+}
+
+fn uct(node: &Node, ln_parent_visits: f64) -> f64 {
+    if node.visits == 0 {
+        return f64::INFINITY;
+    }
+    let visits = node.visits as f64;
+    node.score / visits + EXPLORATION * (ln_parent_visits / visits).sqrt()
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::game::GameState;
+use crate::piece::{Action, Color, Kind, Move, Piece, Square, BOARD_SIZE};
+use crate::rules::{does_piece_block_square, list_possible_moves};
+
+// Position notation, e.g. for the start of a game:
+//
+//     r1@00,r2@01,...,rX@04,...,rB4@21,...,g1@44,...,bB4@67 r 0
+//
+// Every piece is its colour (r, g or b), then its health for a Number, B and its health for a B,
+// or X for a goal, then @ and its rank and file. Pieces are separated by commas and may come in
+// any order. After a space comes the side to move, and optionally another space and the ply count,
+// then another space and how many of those plies were quiet, with nothing taking damage. The quiet
+// count is left out when it's 0.
+//
+// Actions are written as the start and end squares of a move, like 3,2-5,4, or x and the square of
+// the exploding B, like x2,1. When a player types one in, any square a B covers will do as its start,
+// with the end being where that same square goes, and a start square on its own is enough if that
+// piece can only do one thing.
+
+pub(crate) const MAX_HEALTH: i8 = 5;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NotationError(String);
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl std::error::Error for NotationError {}
+
+fn color_letter(color: Color) -> char {
+    match color {
+        Color::Red => 'r',
+        Color::Green => 'g',
+        Color::Blue => 'b',
+    }
+}
+
+fn parse_color(letter: char) -> Result<Color, NotationError> {
+    match letter {
+        'r' => Ok(Color::Red),
+        'g' => Ok(Color::Green),
+        'b' => Ok(Color::Blue),
+        _ => Err(NotationError(format!("`{}` isn't a colour; use r, g or b", letter))),
+    }
+}
+
+fn piece_notation(piece: &Piece) -> String {
+    let kind = match piece.kind {
+        Kind::Number => piece.health.to_string(),
+        Kind::B => format!("B{}", piece.health),
+        Kind::Goal => "X".to_string(),
+    };
+    format!("{}{}@{}{}", color_letter(piece.color), kind, piece.pos.rank, piece.pos.file)
+}
+
+fn parse_piece(token: &str) -> Result<Piece, NotationError> {
+    let error = |message: &str| NotationError(format!("bad piece `{}`: {}", token, message));
+    let (description, square) = token.split_once('@').ok_or_else(|| error("expected something like r3@12"))?;
+    let mut chars = description.chars();
+    let color = parse_color(chars.next().ok_or_else(|| error("missing colour"))?)?;
+    let rest = chars.as_str();
+    let parse_health = |health: &str| match health.parse::<i8>() {
+        Ok(health) if (1..=MAX_HEALTH).contains(&health) => Ok(health),
+        _ => Err(error(&format!("health has to be from 1 to {}", MAX_HEALTH))),
+    };
+    let (kind, health) = if rest == "X" {
+        (Kind::Goal, 1)
+    } else if let Some(health) = rest.strip_prefix('B') {
+        (Kind::B, parse_health(health)?)
+    } else {
+        (Kind::Number, parse_health(rest)?)
+    };
+    let digits = square.chars().map(|c| c.to_digit(10)).collect::<Option<Vec<_>>>();
+    let pos = match digits.as_deref() {
+        Some(&[rank, file]) => Square {rank: rank as usize, file: file as usize},
+        _ => return Err(error("the square has to be a rank digit then a file digit")),
+    };
+    let size = if kind == Kind::B { 2 } else { 1 };
+    if pos.rank + size > BOARD_SIZE || pos.file + size > BOARD_SIZE {
+        return Err(error("it doesn't fit on the board"));
+    }
+    Ok(Piece {color, kind, health, pos})
+}
+
+impl GameState {
+    pub fn to_notation(&self) -> String {
+        let pieces = self.pieces.iter().map(piece_notation).collect::<Vec<_>>().join(",");
+        match self.quiet_plies {
+            0 => format!("{} {} {}", pieces, color_letter(self.turn), self.ply),
+            quiet => format!("{} {} {} {}", pieces, color_letter(self.turn), self.ply, quiet),
+        }
+    }
+
+    pub fn from_notation(notation: &str) -> Result<GameState, NotationError> {
+        let fields = notation.split_whitespace().collect::<Vec<_>>();
+        let (pieces, turn, ply, quiet) = match fields.as_slice() {
+            [pieces, turn] => (pieces, turn, "0", "0"),
+            [pieces, turn, ply] => (pieces, turn, *ply, "0"),
+            [pieces, turn, ply, quiet] => (pieces, turn, *ply, *quiet),
+            _ => return Err(NotationError("expected the pieces, the side to move, and optionally the ply and quiet ply counts".to_string())),
+        };
+        let pieces = pieces.split(',').map(parse_piece).collect::<Result<Vec<_>, _>>()?;
+        let turn = match turn.chars().collect::<Vec<_>>().as_slice() {
+            &[letter] if letter != 'g' => parse_color(letter)?,
+            _ => return Err(NotationError(format!("the side to move has to be r or b, not `{}`", turn))),
+        };
+        let ply = ply.parse().map_err(|_| NotationError(format!("`{}` isn't a ply count", ply)))?;
+        let quiet = quiet.parse().map_err(|_| NotationError(format!("`{}` isn't a quiet ply count", quiet)))?;
+        for (i, piece) in pieces.iter().enumerate() {
+            for other in &pieces[i + 1..] {
+                if piece.color == other.color && piece.kind != Kind::Goal && other.kind != Kind::Goal && overlaps(piece, other) {
+                    return Err(NotationError(format!("{} and {} overlap", piece_notation(piece), piece_notation(other))));
+                }
+            }
+        }
+        let mut state = GameState::from_pieces(pieces, turn);
+        state.ply = ply;
+        state.quiet_plies = quiet;
+        Ok(state)
+    }
+}
+
+fn overlaps(a: &Piece, b: &Piece) -> bool {
+    let footprint = |piece: &Piece| {
+        let mut squares = vec![piece.pos];
+        if piece.kind == Kind::B {
+            squares.push(Square {rank: piece.pos.rank + 1, file: piece.pos.file});
+            squares.push(Square {rank: piece.pos.rank, file: piece.pos.file + 1});
+            squares.push(Square {rank: piece.pos.rank + 1, file: piece.pos.file + 1});
+        }
+        squares
+    };
+    footprint(b).into_iter().any(|square| does_piece_block_square(a, square, false))
+}
+
+impl FromStr for GameState {
+    type Err = NotationError;
+
+    fn from_str(notation: &str) -> Result<GameState, NotationError> {
+        GameState::from_notation(notation)
+    }
+}
+
+impl Square {
+    /// Reads a square written as rank,file, like 3,2.
+    pub fn from_notation(notation: &str) -> Result<Square, NotationError> {
+        parse_square(notation.trim())
+    }
+}
+
+fn parse_square(square: &str) -> Result<Square, NotationError> {
+    let error = || NotationError(format!("`{}` isn't a square; write it as rank,file", square));
+    let (rank, file) = square.split_once(',').ok_or_else(error)?;
+    let rank = rank.trim().parse().map_err(|_| error())?;
+    let file = file.trim().parse().map_err(|_| error())?;
+    if rank >= BOARD_SIZE || file >= BOARD_SIZE {
+        return Err(NotationError(format!("{},{} is off the board", rank, file)));
+    }
+    Ok(Square {rank, file})
+}
+
+impl Action {
+    pub fn to_notation(&self) -> String {
+        match self {
+            Action::Move(mv) => format!("{},{}-{},{}", mv.start.rank, mv.start.file, mv.end.rank, mv.end.file),
+            Action::Explosion(square) => format!("x{},{}", square.rank, square.file),
+        }
+    }
+
+    pub fn from_notation(notation: &str) -> Result<Action, NotationError> {
+        let notation = notation.trim();
+        if let Some(square) = notation.strip_prefix('x') {
+            return Ok(Action::Explosion(parse_square(square)?));
+        }
+        match notation.split_once('-') {
+            Some((start, end)) => Ok(Action::Move(Move {start: parse_square(start)?, end: parse_square(end)?})),
+            None => Err(NotationError(format!("`{}` isn't an action; write a move like 3,2-5,4 or an explosion like x2,1", notation))),
+        }
+    }
+}
+
+impl GameState {
+    /// Reads an action typed by the side to move, checking it against what they may actually play.
+    pub fn parse_action(&self, input: &str) -> Result<Action, NotationError> {
+        let input = input.trim();
+        let list = |actions: &[Action]| actions.iter().map(Action::to_notation).collect::<Vec<_>>().join(" ");
+        let (start, wanted) = if let Some(square) = input.strip_prefix('x') {
+            (parse_square(square)?, Some(Action::Explosion(parse_square(square)?)))
+        } else if let Some((start, end)) = input.split_once('-') {
+            let start = parse_square(start)?;
+            (start, Some(Action::Move(Move {start, end: parse_square(end)?})))
+        } else if input.contains(',') {
+            (parse_square(input)?, None)
+        } else {
+            return Err(NotationError(format!("`{}` isn't an action; write a move like 3,2-5,4 or an explosion like x2,1", input)));
+        };
+        let piece = self.piece_for_input(start)?;
+        let actions = list_possible_moves(&self.occupancy, piece);
+        let wanted = match wanted {
+            Some(Action::Move(mv)) => {
+                // A B is moved by its lowest corner, which goes the same way as the square typed
+                let rank = mv.end.rank.checked_sub(start.rank - piece.pos.rank);
+                let file = mv.end.file.checked_sub(start.file - piece.pos.file);
+                match (rank, file) {
+                    (Some(rank), Some(file)) => Action::Move(Move {start: piece.pos, end: Square {rank, file}}),
+                    _ => return Err(NotationError(format!("{} isn't legal; that piece can play {}", input, list(&actions)))),
+                }
+            },
+            Some(Action::Explosion(_)) if piece.kind != Kind::B => {
+                return Err(NotationError(format!("only a B can explode, and the piece on {},{} isn't one", start.rank, start.file)));
+            },
+            Some(Action::Explosion(_)) => Action::Explosion(piece.pos),
+            None => match actions.as_slice() {
+                [] => return Err(NotationError(format!("the piece on {},{} has no legal moves", start.rank, start.file))),
+                &[only] => only,
+                _ => return Err(NotationError(format!("{} is ambiguous; that piece could play any of {}", input, list(&actions)))),
+            },
+        };
+        if actions.contains(&wanted) {
+            Ok(wanted)
+        } else if actions.is_empty() {
+            Err(NotationError(format!("the piece on {},{} has no legal moves", start.rank, start.file)))
+        } else {
+            Err(NotationError(format!("{} isn't legal; that piece can play {}", wanted.to_notation(), list(&actions))))
+        }
+    }
+
+    // The piece of the side to move covering `square`, for a typed-in action starting there.
+    fn piece_for_input(&self, square: Square) -> Result<&Piece, NotationError> {
+        if let Some(piece) = self.movable_piece_at(square) {
+            return Ok(piece);
+        }
+        match self.pieces.iter().find(|piece| piece.kind != Kind::Goal && does_piece_block_square(piece, square, false)) {
+            Some(piece) => Err(NotationError(format!("the piece on {},{} is {}'s, not {}'s", square.rank, square.file, piece.color, self.turn))),
+            None => Err(NotationError(format!("{} has no piece on {},{}", self.turn, square.rank, square.file))),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = NotationError;
+
+    fn from_str(notation: &str) -> Result<Action, NotationError> {
+        Action::from_notation(notation)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::game::GameState;
+use crate::piece::Action;
+
+// Perft counts the positions reachable in exactly `depth` plies, to check move generation against
+// known numbers. Finished games aren't played on from, so a won position only counts at depth 0.
+
+/// How many leaf positions lie `depth` plies ahead of `state`.
+pub fn perft(state: &mut GameState, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if state.outcome().is_some() {
+        return 0;
+    }
+    let actions = state.legal_actions();
+    if depth == 1 {
+        return actions.len() as u64;
+    }
+    let mut leaves = 0;
+    for action in actions {
+        let undo = state.make(action);
+        leaves += perft(state, depth - 1);
+        state.unmake(undo);
+    }
+    leaves
+}
+
+/// Perft split by the first action, for tracking down where two move generators disagree.
+pub fn divide(state: &mut GameState, depth: u32) -> Vec<(Action, u64)> {
+    if depth == 0 || state.outcome().is_some() {
+        return Vec::new();
+    }
+    state.legal_actions().into_iter().map(|action| {
+        let undo = state.make(action);
+        let leaves = perft(state, depth - 1);
+        state.unmake(undo);
+        (action, leaves)
+    }).collect()
+}
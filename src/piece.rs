@@ -0,0 +1,100 @@
+use std::fmt;
+
+pub const BOARD_SIZE: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Color { // TODO make this have Red and Blue more closely tied than Green somehow
+    Green, // Neutral, and never the side to move; the rules move it at the end of each round
+    Red,
+    Blue,
+}
+impl Color {
+    pub fn opponent(self) -> Color {
+        match self {
+            Color::Red => Color::Blue,
+            Color::Blue => Color::Red,
+            Color::Green => Color::Green,
+        }
+    }
+}
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Color::Green => "Green",
+            Color::Red => "Red",
+            Color::Blue => "Blue",
+        })
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    B, // Bigs are stored by their corner with the smallest coordinates (closest to a0)
+    Goal,
+    Number,
+}
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Kind::B => "B",
+            Kind::Goal => "goal",
+            Kind::Number => "normal",
+        })
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Piece {
+    pub color: Color,
+    pub kind: Kind,
+    pub health: i8,
+    pub pos: Square,
+}
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            Kind::Goal => write!(f, "Goal"),
+            _ => write!(f, "{} {} piece ({} health) on {}", self.color, self.kind, self.health, self.pos),
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Square {
+    pub rank: usize,
+    pub file: usize,
+}
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.rank, self.file)
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Move(Move),
+    Explosion(Square),
+}
+impl Action {
+    /// The square of the piece doing the action.
+    pub fn start(&self) -> Square {
+        match self {
+            Action::Move(m) => m.start,
+            Action::Explosion(s) => *s,
+        }
+    }
+}
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Action::Move(m) => format!("{}", m),
+            Action::Explosion(s) => format!("Explosion on {}", s),
+        })
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub start: Square,
+    pub end: Square,
+}
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Move {} to {}", self.start, self.end)
+    }
+}
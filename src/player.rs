@@ -0,0 +1,122 @@
+use std::fmt;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::game::GameState;
+use crate::mcts::{random_action, Budget, Mcts};
+use crate::piece::Action;
+use crate::search::{AlphaBeta, WIN_SCORE};
+
+/// How good an engine thinks things are for the side to move.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Evaluation {
+    Score(i32), // In evaluator units, where a point of health on a normal piece is worth about 100
+    WinChance(f64),
+}
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Evaluation::Score(score) if score > WIN_SCORE / 2 => write!(f, "wins within {} plies", WIN_SCORE - score),
+            Evaluation::Score(score) if score < -WIN_SCORE / 2 => write!(f, "loses within {} plies", WIN_SCORE + score),
+            Evaluation::Score(score) => write!(f, "{:+.2}", score as f64 / 100.0),
+            Evaluation::WinChance(chance) => write!(f, "{:.0}% to win", chance * 100.0),
+        }
+    }
+}
+
+/// An action an engine would play, with what it thinks of it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Suggestion {
+    pub action: Action,
+    pub evaluation: Evaluation,
+}
+
+/// Anything that can pick moves: a person at a terminal, or an engine.
+pub trait Player {
+    /// A short description, e.g. for announcing whose turn it is.
+    fn name(&self) -> String;
+
+    /// Picks an action for the side to move. Only called when there is at least one legal action.
+    fn choose_action(&mut self, state: &GameState) -> Action;
+
+    /// Whether a person is making the choices, so front ends know whose point of view to show.
+    fn is_human(&self) -> bool {
+        false
+    }
+
+    /// A line of statistics about the last action this player chose, for engines that keep any.
+    fn search_report(&self) -> Option<String> {
+        None
+    }
+
+    /// What this player would do for the side to move and why, for players that can say.
+    fn suggest(&mut self, _state: &GameState) -> Option<Suggestion> {
+        None
+    }
+}
+
+/// Plays uniformly random legal actions.
+#[derive(Clone, Debug)]
+pub struct RandomPlayer {
+    rng: StdRng,
+}
+
+impl RandomPlayer {
+    pub fn new(seed: u64) -> RandomPlayer {
+        RandomPlayer {rng: StdRng::seed_from_u64(seed)}
+    }
+}
+
+impl Player for RandomPlayer {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn choose_action(&mut self, state: &GameState) -> Action {
+        random_action(state, &mut self.rng).expect("no legal actions to choose from")
+    }
+}
+
+impl Player for AlphaBeta {
+    fn name(&self) -> String {
+        match self.time_limit() {
+            Some(limit) => format!("alphabeta {} ms", limit.as_millis()),
+            None => format!("alphabeta depth {}", self.depth()),
+        }
+    }
+
+    fn choose_action(&mut self, state: &GameState) -> Action {
+        self.best_action(state).expect("no legal actions to choose from").0
+    }
+
+    fn search_report(&self) -> Option<String> {
+        match self.threads() {
+            1 => Some(format!("Searched to depth {}, {} nodes.", self.completed_depth(), self.nodes())),
+            threads => Some(format!("Searched to depth {} on {} threads, {} nodes.", self.completed_depth(), threads, self.nodes())),
+        }
+    }
+
+    fn suggest(&mut self, state: &GameState) -> Option<Suggestion> {
+        self.best_action(state).map(|(action, score)| Suggestion {action, evaluation: Evaluation::Score(score)})
+    }
+}
+
+impl Player for Mcts {
+    fn name(&self) -> String {
+        match self.budget() {
+            Budget::Iterations(iterations) => format!("mcts {} iterations", iterations),
+            Budget::Time(limit) => format!("mcts {} ms", limit.as_millis()),
+        }
+    }
+
+    fn choose_action(&mut self, state: &GameState) -> Action {
+        self.best_action(state).expect("no legal actions to choose from")
+    }
+
+    fn search_report(&self) -> Option<String> {
+        Some(format!("Ran {} playouts.", self.iterations()))
+    }
+
+    fn suggest(&mut self, state: &GameState) -> Option<Suggestion> {
+        self.analyse(state).map(|(action, chance)| Suggestion {action, evaluation: Evaluation::WinChance(chance)})
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+use crate::eval::Evaluator;
+use crate::game::GameState;
+use crate::piece::Action;
+use crate::search::{AlphaBeta, MAX_DEPTH, WIN_SCORE};
+
+// A line-based engine protocol modelled on UCI, so GUIs and tournament runners can drive the engine.
+// The controller sends:
+//
+//     uci                                     Reply with id lines and uciok
+//     isready                                 Reply readyok
+//     newgame (or ucinewgame)                 Forget what earlier searches learned
+//     setoption name Threads value <n>        Search on n threads
+//     position startpos [moves <action>...]   Set up the usual start, then play the actions
+//     position <notation> [moves <action>...] Set up a position in position notation instead
+//     go [depth <n>] [movetime <ms>]          Search, reply with info lines and then bestmove
+//     quit                                    Stop the engine
+//
+// Searches finish before the next command is read, so use movetime to bound them. The engine
+// replies to a search with
+//
+//     info depth 4 score cp 36 nodes 28567 time 47 pv 0,3-2,4
+//     bestmove 0,3-2,4
+//
+// where the score is from the side to move's point of view, in hundredths of a point of health on a
+// normal piece, or `score mate <n>` for a win n plies away (negative if it's a loss). The bestmove
+// is `none`, with no info line, if the game is already over. Anything the engine can't make sense of gets
+// an `info string` reply saying why.
+
+pub const ENGINE_NAME: &str = "epic-not-chess-game engine";
+const DEFAULT_DEPTH: u32 = 4;
+
+/// Engine state for one protocol session: the current position and a search whose table lasts the
+/// whole session.
+#[derive(Debug)]
+pub struct Engine {
+    state: GameState,
+    search: AlphaBeta,
+    quit: bool,
+}
+
+impl Default for Engine {
+    fn default() -> Engine {
+        Engine::new(Evaluator::default())
+    }
+}
+
+impl Engine {
+    pub fn new(evaluator: Evaluator) -> Engine {
+        Engine {state: GameState::new(), search: AlphaBeta::new(DEFAULT_DEPTH).with_evaluator(evaluator), quit: false}
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Whether the controller has sent quit.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Handles one line from the controller and returns the lines to send back.
+    pub fn handle(&mut self, line: &str) -> Vec<String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let result = match words.as_slice() {
+            [] => Ok(Vec::new()),
+            ["uci"] => Ok(vec![format!("id name {}", ENGINE_NAME), "option name Threads type spin default 1 min 1".to_string(), "uciok".to_string()]),
+            ["isready"] => Ok(vec!["readyok".to_string()]),
+            ["newgame"] | ["ucinewgame"] => {
+                self.search.table().clear();
+                Ok(Vec::new())
+            },
+            ["setoption", "name", name, "value", value] => self.set_option(name, value).map(|()| Vec::new()),
+            ["position", rest @ ..] => self.set_position(rest).map(|()| Vec::new()),
+            ["go", rest @ ..] => self.go(rest),
+            ["quit"] => {
+                self.quit = true;
+                Ok(Vec::new())
+            },
+            [command, ..] => Err(format!("unknown command `{}`", command)),
+        };
+        result.unwrap_or_else(|e| vec![format!("info string error: {}", e)])
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "Threads" => {
+                let threads = value.parse::<usize>().ok().filter(|&threads| threads > 0)
+                    .ok_or_else(|| format!("Threads has to be a positive number, not `{}`", value))?;
+                self.search = self.search.clone().with_threads(threads);
+                Ok(())
+            },
+            _ => Err(format!("there's no option called {}", name)),
+        }
+    }
+
+    fn set_position(&mut self, words: &[&str]) -> Result<(), String> {
+        let split = words.iter().position(|&word| word == "moves").unwrap_or(words.len());
+        let (position, moves) = words.split_at(split);
+        let mut state = match position {
+            ["startpos"] => GameState::new(),
+            [] => return Err("position needs startpos or a position in notation".to_string()),
+            _ => GameState::from_notation(&position.join(" ")).map_err(|e| e.to_string())?,
+        };
+        for notation in moves.iter().skip(1) {
+            let action = Action::from_notation(notation).map_err(|e| e.to_string())?;
+            if state.winner().is_some() || !state.legal_actions().contains(&action) {
+                return Err(format!("{} isn't legal in that position", notation));
+            }
+            state.apply(action);
+        }
+        self.state = state;
+        Ok(())
+    }
+
+    fn go(&mut self, words: &[&str]) -> Result<Vec<String>, String> {
+        let mut depth = None;
+        let mut movetime = None;
+        let mut words = words.iter();
+        while let Some(&word) = words.next() {
+            let mut number = || words.next().and_then(|value| value.parse::<u64>().ok()).ok_or_else(|| format!("{} needs a number", word));
+            match word {
+                "depth" => depth = Some(number()?.clamp(1, MAX_DEPTH as u64) as u32),
+                "movetime" => movetime = Some(Duration::from_millis(number()?)),
+                _ => return Err(format!("go doesn't understand `{}`", word)),
+            }
+        }
+        if self.state.outcome().is_some() {
+            return Ok(vec!["bestmove none".to_string()]);
+        }
+        let depth = depth.unwrap_or(if movetime.is_some() { MAX_DEPTH } else { DEFAULT_DEPTH });
+        // Clones share the table, so every search in the session benefits from the ones before
+        let mut search = self.search.clone().with_depth(depth);
+        if let Some(limit) = movetime {
+            search = search.with_time_limit(limit);
+        }
+        let start = Instant::now();
+        let best = search.best_action(&self.state);
+        let millis = start.elapsed().as_millis();
+        Ok(match best {
+            Some((action, score)) => vec![
+                format!("info depth {} score {} nodes {} time {} pv {}", search.completed_depth(), score_notation(score), search.nodes(), millis, action.to_notation()),
+                format!("bestmove {}", action.to_notation()),
+            ],
+            None => vec!["bestmove none".to_string()],
+        })
+    }
+}
+
+fn score_notation(score: i32) -> String {
+    if score > WIN_SCORE / 2 {
+        format!("mate {}", WIN_SCORE - score)
+    } else if score < -WIN_SCORE / 2 {
+        format!("mate -{}", WIN_SCORE + score)
+    } else {
+        format!("cp {}", score)
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use crate::game::{GameState, Outcome};
+use crate::piece::{Action, Color};
+
+// Game records look like PGN:
+//
+//     [Red "human"]
+//     [Blue "alphabeta depth 3"]
+//     [Result "1-0"]
+//
+//     1. 0,3-2,4 9,3-7,4 2. x2,1 8,1-7,1 1-0
+//
+// Headers are free-form `[Name "value"]` lines, with a `\` in front of any `"` or `\` in the value.
+// A `Position` header holds the starting position in position notation when it isn't the usual
+// one. The body numbers each pair of actions and ends with the result: 1-0 if Red won, 0-1 if Blue
+// won, 1/2-1/2 for a draw, or * if the game isn't over. A `Result` header, if there is one, wins
+// over what the final position says, and a `Termination` header says how a game ended other than
+// by reaching the goals.
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(std::io::Error),
+    Syntax(String),
+}
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(e) => write!(f, "couldn't access game record: {}", e),
+            RecordError::Syntax(message) => write!(f, "bad game record: {}", message),
+        }
+    }
+}
+impl std::error::Error for RecordError {}
+
+/// The result token for a game that ended with `outcome`, or is still going if it's None.
+pub fn result_notation(outcome: Option<Outcome>) -> &'static str {
+    match outcome {
+        Some(Outcome::Win(winner, _)) => win_notation(winner),
+        Some(Outcome::Draw(_)) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// The result token for a game `winner` won, however it came about.
+pub fn win_notation(winner: Color) -> &'static str {
+    if winner == Color::Blue { "0-1" } else { "1-0" }
+}
+
+/// A whole game: where it started and every action played since.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GameRecord {
+    headers: Vec<(String, String)>,
+    start: GameState,
+    actions: Vec<Action>,
+}
+
+impl GameRecord {
+    pub fn new(start: GameState) -> GameRecord {
+        GameRecord {headers: Vec::new(), start, actions: Vec::new()}
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Sets a header, keeping its place if it was already there.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        match self.headers.iter_mut().find(|(n, _)| n == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.headers.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn start(&self) -> &GameState {
+        &self.start
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn push(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    pub fn pop(&mut self) -> Option<Action> {
+        self.actions.pop()
+    }
+
+    /// The position after the first `count` actions.
+    pub fn position_after(&self, count: usize) -> GameState {
+        let mut state = self.start.clone();
+        for &action in &self.actions[..count] {
+            state.apply(action);
+        }
+        state
+    }
+
+    /// The position after every action.
+    pub fn current_position(&self) -> GameState {
+        self.position_after(self.actions.len())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (name, value) in &self.headers {
+            if name != "Position" {
+                text += &format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\""));
+            }
+        }
+        if self.start != GameState::new() {
+            text += &format!("[Position \"{}\"]\n", self.start.to_notation());
+        }
+        text.push('\n');
+        let mut state = self.start.clone();
+        let mut line = String::new();
+        for &action in &self.actions {
+            if state.turn() == Color::Red || line.is_empty() {
+                let number = state.ply() / 2 + 1;
+                let token = if state.turn() == Color::Red { format!("{}.", number) } else { format!("{}...", number) };
+                push_token(&mut text, &mut line, &token);
+            }
+            push_token(&mut text, &mut line, &action.to_notation());
+            state.apply(action);
+        }
+        let result = self.header("Result").unwrap_or_else(|| result_notation(state.outcome()));
+        push_token(&mut text, &mut line, result);
+        text + &line + "\n"
+    }
+
+    /// Reads a record back, checking that every action in it was legal when it was played.
+    pub fn parse(text: &str) -> Result<GameRecord, RecordError> {
+        let syntax_error = |message: String| RecordError::Syntax(message);
+        let mut headers = Vec::new();
+        let mut body = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.strip_suffix(']').ok_or_else(|| syntax_error(format!("unfinished header `{}`", line)))?;
+                let (name, value) = header.split_once(' ').ok_or_else(|| syntax_error(format!("header `{}` has no value", line)))?;
+                let value = value.trim();
+                let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+                headers.push((name.to_string(), unescape(value)));
+            } else {
+                body += line;
+                body.push(' ');
+            }
+        }
+        let start = match headers.iter().find(|(name, _)| name == "Position") {
+            Some((_, position)) => GameState::from_notation(position).map_err(|e| syntax_error(format!("Position header: {}", e)))?,
+            None => GameState::new(),
+        };
+        let mut record = GameRecord {headers, start, actions: Vec::new()};
+        let mut state = record.start.clone();
+        for token in body.split_whitespace() {
+            if token.ends_with('.') && token.trim_end_matches('.').parse::<u32>().is_ok() {
+                continue;
+            }
+            if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token) {
+                break;
+            }
+            let action = Action::from_notation(token).map_err(|e| syntax_error(e.to_string()))?;
+            // Games that went on past a draw are still readable; only a win has to end them
+            if state.winner().is_some() || !state.legal_actions().contains(&action) {
+                return Err(syntax_error(format!("{} isn't legal after {} actions", token, record.actions.len())));
+            }
+            state.apply(action);
+            record.actions.push(action);
+        }
+        Ok(record)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RecordError> {
+        fs::write(path, self.to_text()).map_err(RecordError::Io)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameRecord, RecordError> {
+        GameRecord::parse(&fs::read_to_string(path).map_err(RecordError::Io)?)
+    }
+}
+
+// Undoes the escaping of `"` and `\` in a header value.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' { chars.next().unwrap_or(c) } else { c });
+    }
+    unescaped
+}
+
+// Adds a token to the body, wrapping lines at 80 columns.
+fn push_token(text: &mut String, line: &mut String, token: &str) {
+    if !line.is_empty() && line.len() + 1 + token.len() > 80 {
+        *text += line;
+        text.push('\n');
+        line.clear();
+    }
+    if !line.is_empty() {
+        line.push(' ');
+    }
+    *line += token;
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write;
+use crate::piece::{Color, Kind, Piece, Square, BOARD_SIZE};
+
+const TERMINAL_COLOR_GREEN:   &str = "\x1B[32m";
+const TERMINAL_COLOR_RED:     &str = "\x1B[31m";
+const TERMINAL_COLOR_BLUE:    &str = "\x1B[34m";
+const TERMINAL_COLOR_DEFAULT: &str = "\x1B[0m";
+
+/// Which way up the board is drawn.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Perspective {
+    Red, // Red's home rank at the bottom
+    Blue, // Blue's home rank at the bottom, so the board is turned all the way around
+    Spectator, // Turned sideways, with Red on the left and Blue on the right
+}
+impl Perspective {
+    /// The next perspective round, for flipping the board.
+    pub fn flipped(self) -> Perspective {
+        match self {
+            Perspective::Red => Perspective::Blue,
+            Perspective::Blue => Perspective::Spectator,
+            Perspective::Spectator => Perspective::Red,
+        }
+    }
+
+    /// The (row, column) a square is drawn at, counting from the top left.
+    pub fn to_screen(self, square: Square) -> (usize, usize) {
+        let last = BOARD_SIZE - 1;
+        match self {
+            Perspective::Red => (last - square.rank, square.file),
+            Perspective::Blue => (square.rank, last - square.file),
+            Perspective::Spectator => (square.file, square.rank),
+        }
+    }
+
+    /// The square drawn at (row, column); the inverse of `to_screen`.
+    pub fn square_at(self, row: usize, column: usize) -> Square {
+        let last = BOARD_SIZE - 1;
+        match self {
+            Perspective::Red => Square {rank: last - row, file: column},
+            Perspective::Blue => Square {rank: row, file: last - column},
+            Perspective::Spectator => Square {rank: column, file: row},
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RenderedPiece {
+    color: Color,
+    appearance: char,
+}
+
+const NOT_A_RENDERED_PIECE: RenderedPiece = RenderedPiece {
+    color: Color::Green,
+    appearance: ' ',
+};
+
+/// Draws the board as text, one line per row, with coordinates along the left and bottom edges.
+pub fn render(pieces: &[Piece], perspective: Perspective, use_color: bool) -> String {
+    let mut rendered_board = [[NOT_A_RENDERED_PIECE; BOARD_SIZE]; BOARD_SIZE];
+    let mut draw = |square: Square, color: Color, appearance: char| {
+        if square.rank < BOARD_SIZE && square.file < BOARD_SIZE {
+            let (row, column) = perspective.to_screen(square);
+            rendered_board[row][column] = RenderedPiece {color, appearance};
+        }
+    };
+    // Goals go down first so that anything standing on them is drawn over them
+    for piece in pieces.iter().filter(|piece| piece.kind == Kind::Goal) {
+        draw(piece.pos, piece.color, 'X');
+    }
+    for piece in pieces {
+        match piece.kind {
+            Kind::B => {
+                let corners = [
+                    piece.pos,
+                    Square {rank: piece.pos.rank + 1, file: piece.pos.file},
+                    Square {rank: piece.pos.rank, file: piece.pos.file + 1},
+                    Square {rank: piece.pos.rank + 1, file: piece.pos.file + 1},
+                ];
+                let screen_corners = corners.iter().map(|&square| perspective.to_screen(square)).collect::<Vec<_>>();
+                let top = screen_corners.iter().map(|&(row, _)| row).min().unwrap();
+                let left = screen_corners.iter().map(|&(_, column)| column).min().unwrap();
+                for (&square, &(row, column)) in corners.iter().zip(&screen_corners) {
+                    let appearance = match (row == top, column == left) {
+                        (true, true) => '╔',
+                        (true, false) => '╗',
+                        (false, true) => '╚',
+                        (false, false) => '╝',
+                    };
+                    draw(square, piece.color, appearance);
+                }
+            },
+            Kind::Goal => (),
+            Kind::Number => draw(piece.pos, piece.color, (b'0' + (piece.health as u8)) as char),
+        }
+    }
+
+    // Rows are ranks and columns are files, except when the board is turned sideways
+    let sideways = perspective == Perspective::Spectator;
+    let row_label = |row| {
+        let square = perspective.square_at(row, 0);
+        if sideways { square.file } else { square.rank }
+    };
+    let column_label = |column| {
+        let square = perspective.square_at(0, column);
+        if sideways { square.rank } else { square.file }
+    };
+    let mut rendered = String::new();
+    for (row, rendered_row) in rendered_board.iter().enumerate() {
+        write!(rendered, "{} ", row_label(row)).unwrap();
+        for rendered_piece in rendered_row {
+            if use_color {
+                let color = match rendered_piece.color {
+                    Color::Green => TERMINAL_COLOR_GREEN,
+                    Color::Red   => TERMINAL_COLOR_RED,
+                    Color::Blue  => TERMINAL_COLOR_BLUE,
+                };
+                write!(rendered, "{}{}{}", color, rendered_piece.appearance, TERMINAL_COLOR_DEFAULT).unwrap();
+            } else {
+                rendered.push(rendered_piece.appearance);
+            }
+        }
+        rendered.push('\n');
+    }
+    rendered.push_str(if sideways { "fr" } else { "rf" });
+    for column in 0..BOARD_SIZE {
+        write!(rendered, "{}", column_label(column)).unwrap();
+    }
+    rendered
+}
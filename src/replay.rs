@@ -0,0 +1,52 @@
+use std::io;
+use epic_not_chess_game::{render, GameRecord, Perspective, Undo};
+
+// Steps through a recorded game, forward and backward, drawing every position.
+pub fn replay(record: &GameRecord, use_color: bool) {
+    for (name, value) in record.headers() {
+        println!("{}: {}", name, value);
+    }
+    println!("Press Enter or type n for the next action, p for the previous one, s for the start, e for the end, f to flip the board and q to quit.");
+    let actions = record.actions();
+    let mut state = record.start().clone();
+    let mut undos: Vec<Undo> = Vec::new();
+    let mut perspective = Perspective::Red;
+    loop {
+        println!("{}", render(state.pieces(), perspective, use_color));
+        match undos.last() {
+            Some(undo) => println!("After action {} of {}: {}", undos.len(), actions.len(), undo.action().to_notation()),
+            None => println!("Starting position; {} actions to go.", actions.len()),
+        }
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).expect("Failed to read line") == 0 {
+            return;
+        }
+        match line.trim() {
+            "" | "n" => {
+                match actions.get(undos.len()) {
+                    Some(&action) => undos.push(state.make(action)),
+                    None => println!("That was the last action."),
+                }
+            },
+            "p" => {
+                match undos.pop() {
+                    Some(undo) => state.unmake(undo),
+                    None => println!("Already at the start."),
+                }
+            },
+            "s" => {
+                while let Some(undo) = undos.pop() {
+                    state.unmake(undo);
+                }
+            },
+            "e" => {
+                while let Some(&action) = actions.get(undos.len()) {
+                    undos.push(state.make(action));
+                }
+            },
+            "f" => perspective = perspective.flipped(),
+            "q" => return,
+            other => println!("Unknown replay command `{}`.", other),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::cmp;
+use std::fmt;
+use crate::bitboard::index;
+use crate::game::GameState;
+use crate::piece::{Action, Color, Kind, Piece, Square};
+use crate::rules::{add_usize_int, did_player_win, does_piece_block_square, neutral_move, EXPLOSION_OFFSETS};
+use crate::zobrist;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Damage {
+    pub piece: Piece, // As it stood before the action
+    pub amount: i8,
+}
+
+/// Everything that happened when an action was played.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ActionReport {
+    pub action: Action,
+    pub damaged: Vec<Damage>,
+    pub destroyed: Vec<Piece>, // As they stood before the action
+    pub mover_end: Option<Square>, // None if the moving piece didn't survive
+    pub neutral: Vec<ActionReport>, // Green's moves after the action
+}
+impl fmt::Display for ActionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.action)?;
+        for damage in &self.damaged {
+            write!(f, "\n  {} took {} damage", damage.piece, damage.amount)?;
+        }
+        for piece in &self.destroyed {
+            write!(f, "\n  {} was destroyed", piece)?;
+        }
+        if let (Action::Move(_), Some(end)) = (self.action, self.mover_end) {
+            write!(f, "\n  The piece ended up on {}", end)?;
+        }
+        for report in &self.neutral {
+            write!(f, "\nThen Green: {}", report)?;
+        }
+        Ok(())
+    }
+}
+
+/// What `GameState::make` changed, so `GameState::unmake` can put it back exactly.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Undo {
+    step: Step,
+    neutral: Vec<Step>, // Green's moves after the action, in the order they were made
+    turn: Color,
+    ply: u32,
+    hash: u64,
+    quiet_plies: u32,
+}
+impl Undo {
+    pub fn action(&self) -> Action {
+        self.step.action
+    }
+}
+
+// One piece's move or explosion. Indices refer to the piece list as it was just before it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Step {
+    action: Action,
+    mover: usize,
+    damaged: Vec<(usize, i8)>,
+    removed: Vec<(usize, Piece)>, // Ascending by index, as they stood before the step
+}
+impl Step {
+    fn was_removed(&self, index: usize) -> bool {
+        self.removed.iter().any(|&(i, _)| i == index)
+    }
+
+    fn changed_anything(&self) -> bool {
+        !self.damaged.is_empty() || !self.removed.is_empty()
+    }
+
+    // What the step did, given the pieces as they were before it.
+    fn report(&self, before: &[Piece]) -> ActionReport {
+        let mover_end = match self.action {
+            Action::Move(mv) if !self.was_removed(self.mover) => Some(mv.end),
+            _ => None,
+        };
+        ActionReport {
+            action: self.action,
+            damaged: self.damaged.iter().map(|&(i, amount)| Damage {piece: before[i], amount}).collect(),
+            destroyed: self.removed.iter().map(|&(_, piece)| piece).collect(),
+            mover_end,
+            neutral: Vec::new(),
+        }
+    }
+}
+
+// Plays `action` for the piece at `index`, updating the pieces, occupancy and hash but not the turn.
+fn make_step(state: &mut GameState, index: usize, action: Action) -> Step {
+    let pieces = &mut state.pieces;
+    let occupancy = &mut state.occupancy;
+    let hash = &mut state.hash;
+    let color = pieces[index].color;
+    let mut damaged = Vec::new();
+    let mut take_damage = |damaged_index: usize, amount: i8| {
+        match damaged.iter_mut().find(|(i, _)| *i == damaged_index) {
+            Some((_, total)) => *total += amount,
+            None => damaged.push((damaged_index, amount)),
+        }
+    };
+    match action {
+        Action::Explosion(sq) => {
+            for offset in &EXPLOSION_OFFSETS {
+                let damaged_square = Square {rank: add_usize_int(sq.rank, offset[0]), file: add_usize_int(sq.file, offset[1])};
+                for (damaged_index, damaged_piece) in pieces.iter().enumerate() {
+                    if does_piece_block_square(damaged_piece, damaged_square, false) {
+                        take_damage(damaged_index, 1);
+                    }
+                }
+            }
+        },
+        Action::Move(mv) => {
+            let big = pieces[index].kind == Kind::B;
+            let mut damage = pieces[index].health;
+            let mut total_damage = 0;
+            for (attacked_index, attacked_piece) in pieces.iter().enumerate() {
+                if attacked_piece.color != color && does_piece_block_square(attacked_piece, mv.end, big) {
+                    damage = cmp::min(damage, attacked_piece.health);
+                    take_damage(attacked_index, damage);
+                    total_damage += damage;
+                }
+            }
+            if total_damage > 0 {
+                take_damage(index, total_damage);
+            }
+        },
+    }
+
+    let mut removed = Vec::new();
+    for &(i, amount) in &damaged {
+        if pieces[i].health <= amount {
+            removed.push((i, pieces[i]));
+        }
+    }
+    if let Action::Explosion(_) = action {
+        // The big that exploded is always destroyed
+        removed.push((index, pieces[index]));
+    }
+    removed.sort_by_key(|&(i, _)| i);
+    // Each change XORs the piece's old Zobrist key out of the hash and its new one in
+    for &(i, amount) in &damaged {
+        *hash ^= zobrist::piece_key(&pieces[i]);
+        pieces[i].health -= amount;
+        *hash ^= zobrist::piece_key(&pieces[i]);
+    }
+    if let Action::Move(mv) = action {
+        *hash ^= zobrist::piece_key(&pieces[index]);
+        occupancy.lift(&pieces[index]);
+        pieces[index].pos = mv.end;
+        occupancy.place(&pieces[index]);
+        *hash ^= zobrist::piece_key(&pieces[index]);
+    }
+    for &(i, _) in removed.iter().rev() {
+        let piece = pieces.remove(i);
+        occupancy.lift(&piece);
+        *hash ^= zobrist::piece_key(&piece);
+    }
+    Step {action, mover: index, damaged, removed}
+}
+
+// Puts the pieces and occupancy back as they were before `step`. The hash is restored separately.
+fn unmake_step(state: &mut GameState, step: &Step) {
+    let pieces = &mut state.pieces;
+    let occupancy = &mut state.occupancy;
+    for &(i, piece) in &step.removed {
+        pieces.insert(i, piece);
+        occupancy.place(&piece);
+    }
+    for &(i, amount) in &step.damaged {
+        if !step.was_removed(i) {
+            pieces[i].health += amount;
+        }
+    }
+    if !step.was_removed(step.mover) {
+        occupancy.lift(&pieces[step.mover]);
+        pieces[step.mover].pos = step.action.start();
+        occupancy.place(&pieces[step.mover]);
+    }
+}
+
+// Green's moves at the end of a round. See `neutral_move` for how it picks them.
+fn make_neutral_steps(state: &mut GameState) -> Vec<Step> {
+    let is_green_number = |piece: &Piece| piece.color == Color::Green && piece.kind == Kind::Number;
+    let mut starts = state.pieces.iter().filter(|piece| is_green_number(piece)).map(|piece| piece.pos).collect::<Vec<_>>();
+    starts.sort_by_key(|&square| index(square));
+    let mut steps = Vec::new();
+    for start in starts {
+        // Green never fights Green, so every Green piece is still where it started when its go comes
+        let mover = state.pieces.iter().position(|piece| piece.pos == start && is_green_number(piece)).unwrap();
+        if let Some(mv) = neutral_move(&state.occupancy, &state.pieces[mover]) {
+            steps.push(make_step(state, mover, Action::Move(mv)));
+        }
+    }
+    steps
+}
+
+pub(crate) fn make_action(state: &mut GameState, action: Action) -> Undo {
+    let start = action.start();
+    let turn = state.turn;
+    let old_hash = state.hash;
+    let mover = state.pieces.iter()
+        .position(|piece| piece.pos == start && piece.color == turn && piece.kind != Kind::Goal)
+        .unwrap_or_else(|| panic!("{} has no piece on {} to play {}", turn, start, action));
+    let step = make_step(state, mover, action);
+    let neutral = if turn == Color::Blue && !did_player_win(&state.occupancy, Color::Blue) {
+        make_neutral_steps(state)
+    } else {
+        Vec::new()
+    };
+    state.hash ^= zobrist::turn_key(turn) ^ zobrist::turn_key(turn.opponent());
+
+    let quiet_plies = state.quiet_plies;
+    let progress = step.changed_anything() || neutral.iter().any(Step::changed_anything);
+    state.quiet_plies = if progress { 0 } else { quiet_plies + 1 };
+    state.history.push(old_hash);
+    let undo = Undo {step, neutral, turn, ply: state.ply, hash: old_hash, quiet_plies};
+    state.turn = turn.opponent();
+    state.ply += 1;
+    undo
+}
+
+pub(crate) fn unmake_action(state: &mut GameState, undo: Undo) {
+    for step in undo.neutral.iter().rev() {
+        unmake_step(state, step);
+    }
+    unmake_step(state, &undo.step);
+    state.turn = undo.turn;
+    state.ply = undo.ply;
+    state.hash = undo.hash;
+    state.quiet_plies = undo.quiet_plies;
+    state.history.pop();
+}
+
+/// Plays `action` for the side to move, passes the turn, and reports what it did, along with
+/// anything Green did afterwards. This is the one place the damage rules live; `action` is expected
+/// to be legal.
+pub fn resolve_action(state: &mut GameState, action: Action) -> ActionReport {
+    let undo = make_action(state, action);
+    // Taking the steps back one at a time shows the pieces as each step found them
+    let mut earlier = state.clone();
+    let mut neutral = Vec::new();
+    for step in undo.neutral.iter().rev() {
+        unmake_step(&mut earlier, step);
+        neutral.insert(0, step.report(&earlier.pieces));
+    }
+    unmake_step(&mut earlier, &undo.step);
+    ActionReport {neutral, ..undo.step.report(&earlier.pieces)}
+}
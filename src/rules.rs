@@ -0,0 +1,129 @@
+use crate::bitboard::{bit, index, number_moves, squares, Occupancy, BIG_FOOTPRINTS, BIG_MOVES};
+use crate::piece::{Action, Color, Kind, Move, Piece, Square, BOARD_SIZE};
+
+// The 12 squares surrounding a B's 2x2 footprint, which all take one damage when it explodes.
+pub(crate) const EXPLOSION_OFFSETS: [[i8; 2]; 12] = [[-1, -1], [0, -1], [1, -1], [2, -1], [2, 0], [2, 1], [2, 2], [1, 2], [0, 2], [-1, 2], [-1, 1], [-1, 0]];
+
+pub(crate) fn does_piece_block_square(potential_blocker: &Piece, square: Square, is_big: bool) -> bool {
+    if is_big {
+        // is_big: The square itself has a big hitbox. This is distinct from potential_blocker being a B.
+        does_piece_block_square(potential_blocker, square, false) ||
+        does_piece_block_square(potential_blocker, Square {rank: square.rank + 1, file: square.file}, false) ||
+        does_piece_block_square(potential_blocker, Square {rank: square.rank, file: square.file + 1}, false) ||
+        does_piece_block_square(potential_blocker, Square {rank: square.rank + 1, file: square.file + 1}, false)
+    } else {
+        match potential_blocker.kind {
+            Kind::B => {
+                (potential_blocker.pos == square) ||
+                (Square {
+                    rank: potential_blocker.pos.rank + 1,
+                    file: potential_blocker.pos.file,
+                } == square) ||
+                (Square {
+                    rank: potential_blocker.pos.rank,
+                    file: potential_blocker.pos.file + 1,
+                } == square) ||
+                (Square {
+                    rank: potential_blocker.pos.rank + 1,
+                    file: potential_blocker.pos.file + 1,
+                } == square)
+            },
+            Kind::Number => potential_blocker.pos == square,
+            Kind::Goal => false,
+        }
+    }
+}
+
+pub(crate) fn add_usize_int(a: usize, b: i8) -> usize {
+    if a as i8 + b >= 0 {
+        (a as i8 + b) as usize
+    } else {
+        BOARD_SIZE // represents uninhabitable square
+    }
+}
+
+pub(crate) fn list_possible_moves(occupancy: &Occupancy, piece: &Piece) -> Vec<Action> {
+    let from = index(piece.pos);
+    let own = occupancy.blockers(piece.color);
+    let ends = match piece.kind {
+        Kind::Goal => return Vec::new(),
+        Kind::Number => number_moves(piece.color, piece.health)[from] & !own,
+        Kind::B => {
+            // A B can't be blocked by itself, and has to fit its whole footprint in
+            let others = own & !BIG_FOOTPRINTS[from];
+            squares(BIG_MOVES[from]).filter(|&end| {
+                let footprint = BIG_FOOTPRINTS[index(end)];
+                footprint != 0 && footprint & others == 0
+            }).fold(0, |ends, end| ends | bit(end))
+        },
+    };
+    let mut ret: Vec<Action> = squares(ends).map(|end| Action::Move(Move {start: piece.pos, end})).collect();
+    if let Kind::B = piece.kind {
+        ret.push(Action::Explosion(piece.pos));
+    }
+    ret
+}
+
+pub(crate) fn find_possible_moves(pieces: &[Piece], occupancy: &Occupancy, turn: Color) -> Vec<Action> {
+    pieces.iter().filter(|piece| piece.color == turn).flat_map(|piece|
+        list_possible_moves(occupancy, piece)
+    ).collect()
+}
+
+// Like `!find_possible_moves(..).is_empty()`, stopping at the first piece that can do anything.
+pub(crate) fn has_possible_moves(pieces: &[Piece], occupancy: &Occupancy, turn: Color) -> bool {
+    let own = occupancy.blockers(turn);
+    pieces.iter().filter(|piece| piece.color == turn).any(|piece| match piece.kind {
+        Kind::B => true, // It can always explode
+        Kind::Number => number_moves(turn, piece.health)[index(piece.pos)] & !own != 0,
+        Kind::Goal => false,
+    })
+}
+
+// Like `find_possible_moves(..).len()`, without building the list.
+pub(crate) fn count_possible_moves(pieces: &[Piece], occupancy: &Occupancy, turn: Color) -> usize {
+    let own = occupancy.blockers(turn);
+    pieces.iter().filter(|piece| piece.color == turn).map(|piece| match piece.kind {
+        Kind::Number => (number_moves(turn, piece.health)[index(piece.pos)] & !own).count_ones() as usize,
+        _ => list_possible_moves(occupancy, piece).len(),
+    }).sum()
+}
+
+// Green is a neutral side that nobody controls. Once Blue has played, finishing a round, each Green
+// Number takes one step toward the nearest Red or Blue piece, the lowest square going first, and
+// fights whatever it steps onto just as a player's piece would. It only steps if that gets it closer,
+// by Manhattan distance, taking the first such square in board order. Green Bs and goals stay put.
+// Green sits the round out if Blue's action just won the game, so it can't take Blue's win away.
+pub(crate) fn neutral_move(occupancy: &Occupancy, piece: &Piece) -> Option<Move> {
+    let targets = occupancy.blockers(Color::Red) | occupancy.blockers(Color::Blue);
+    if piece.kind != Kind::Number || targets == 0 {
+        return None;
+    }
+    let distance = |from: Square| squares(targets).map(|to| from.rank.abs_diff(to.rank) + from.file.abs_diff(to.file)).min().unwrap();
+    let ends = number_moves(piece.color, piece.health)[index(piece.pos)] & !occupancy.blockers(piece.color);
+    squares(ends)
+        .filter(|&end| distance(end) < distance(piece.pos))
+        .min_by_key(|&end| distance(end))
+        .map(|end| Move {start: piece.pos, end})
+}
+
+// The enemy goal squares `player` has to cover to win.
+pub(crate) fn goal_squares(player: Color) -> [Square; 2] {
+    match player {
+        Color::Blue => {
+            // Positions of Red's goals
+            [Square{rank: 0, file: 4}, Square{rank: 0, file: 5}]
+        },
+        _ => {
+            // Positions of Blue's goals
+            [Square{rank: 9, file: 4}, Square{rank: 9, file: 5}]
+        },
+    }
+}
+
+pub(crate) fn did_player_win(occupancy: &Occupancy, player: Color) -> bool {
+    // Only your own pieces count toward your win
+    let [goal1, goal2] = goal_squares(player);
+    let goals = bit(goal1) | bit(goal2);
+    occupancy.blockers(player) & goals == goals
+}
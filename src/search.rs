@@ -0,0 +1,286 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::eval::Evaluator;
+use crate::game::{GameState, Outcome};
+use crate::piece::Action;
+use crate::table::{Bound, TableEntry, TranspositionTable};
+
+pub const WIN_SCORE: i32 = 1_000_000;
+const INFINITY: i32 = WIN_SCORE + 1;
+// Scores this close to WIN_SCORE are wins a known number of plies away
+const WIN_THRESHOLD: i32 = WIN_SCORE - 1000;
+const DEFAULT_TABLE_SIZE: usize = 1 << 16;
+/// The deepest a search will go, for searches that are only limited by time.
+pub const MAX_DEPTH: u32 = 64;
+// How many nodes go by between checks of the clock
+const NODES_PER_CLOCK_CHECK: u64 = 1024;
+
+/// Iterative deepening negamax with alpha-beta pruning and a transposition table.
+/// With more than one thread it runs lazy SMP: helper threads search the same position in a
+/// different order, and what they store in the shared table speeds up the main thread.
+/// Clones share the same table.
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    depth: u32,
+    time_limit: Option<Duration>,
+    threads: usize,
+    evaluator: Evaluator,
+    table: Arc<TranspositionTable>,
+    nodes: u64,
+    completed_depth: u32,
+    deadline: Option<Instant>,
+    stopped: bool,
+    helper: usize, // 0 for the main search, otherwise which helper thread this is
+    abort: Option<Arc<AtomicBool>>, // Set when a helper's main search is done
+}
+
+impl AlphaBeta {
+    pub fn new(depth: u32) -> AlphaBeta {
+        AlphaBeta {
+            depth: depth.max(1),
+            time_limit: None,
+            threads: 1,
+            evaluator: Evaluator::default(),
+            table: Arc::new(TranspositionTable::new(DEFAULT_TABLE_SIZE)),
+            nodes: 0,
+            completed_depth: 0,
+            deadline: None,
+            stopped: false,
+            helper: 0,
+            abort: None,
+        }
+    }
+
+    /// Stops deepening once `limit` has passed, and plays the best action of the last depth that
+    /// finished. Depth 1 always finishes, however long it takes.
+    pub fn with_time_limit(mut self, limit: Duration) -> AlphaBeta {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Uses a transposition table with room for `size` entries instead of the default.
+    pub fn with_table_size(mut self, size: usize) -> AlphaBeta {
+        self.table = Arc::new(TranspositionTable::new(size));
+        self
+    }
+
+    /// Searches on `threads` threads at once. The results are no longer repeatable from run to run.
+    pub fn with_threads(mut self, threads: usize) -> AlphaBeta {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn with_depth(mut self, depth: u32) -> AlphaBeta {
+        self.depth = depth.max(1);
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> AlphaBeta {
+        self.evaluator = evaluator;
+        self
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// How many positions the last search visited, over all its iterations and threads.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// The deepest iteration the last search finished.
+    pub fn completed_depth(&self) -> u32 {
+        self.completed_depth
+    }
+
+    pub fn table(&self) -> &TranspositionTable {
+        &self.table
+    }
+
+    /// The best action for the side to move with its score, or None if it has no legal actions.
+    pub fn best_action(&mut self, state: &GameState) -> Option<(Action, i32)> {
+        if self.threads == 1 {
+            return self.deepen(state);
+        }
+        let abort = Arc::new(AtomicBool::new(false));
+        let mut helpers = (1..self.threads).map(|helper| {
+            AlphaBeta {helper, threads: 1, abort: Some(abort.clone()), ..self.clone()}
+        }).collect::<Vec<_>>();
+        let best = thread::scope(|scope| {
+            for helper in &mut helpers {
+                scope.spawn(move || helper.deepen(state));
+            }
+            let best = self.deepen(state);
+            abort.store(true, Ordering::Relaxed);
+            best
+        });
+        self.nodes += helpers.iter().map(|helper| helper.nodes).sum::<u64>();
+        best
+    }
+
+    fn deepen(&mut self, state: &GameState) -> Option<(Action, i32)> {
+        self.nodes = 0;
+        self.completed_depth = 0;
+        self.stopped = false;
+        self.deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let mut state = state.clone();
+        let mut best = None;
+        for depth in 1..=self.depth {
+            // Half the helpers stay a ply ahead, so the table fills with deeper results sooner
+            let depth = if self.helper % 2 == 1 { (depth + 1).min(self.depth) } else { depth };
+            // Each iteration tries the previous one's best action first, through the table
+            match self.search_root(&mut state, depth) {
+                Some(result) if !self.stopped => {
+                    best = Some(result);
+                    self.completed_depth = depth;
+                },
+                _ => break,
+            }
+            if let Some((_, score)) = best {
+                if score.abs() > WIN_THRESHOLD {
+                    // A forced win or loss was found, and deeper searches won't change it
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn search_root(&mut self, state: &mut GameState, depth: u32) -> Option<(Action, i32)> {
+        let mut best = None;
+        let mut alpha = -INFINITY;
+        let mut actions = state.legal_actions();
+        order_actions(&mut actions, self.table.probe(state.hash()).and_then(|entry| entry.best));
+        if self.helper > 0 && !actions.is_empty() {
+            // Helpers start on different actions so they don't all search the same tree
+            let len = actions.len();
+            actions.rotate_left(self.helper % len);
+        }
+        for action in actions {
+            let undo = state.make(action);
+            let score = -self.negamax(state, depth - 1, 1, -INFINITY, -alpha);
+            state.unmake(undo);
+            if self.stopped {
+                return None;
+            }
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some((action, score));
+            }
+        }
+        if let Some((action, score)) = best {
+            self.table.store(TableEntry {key: state.hash(), depth, score, bound: Bound::Exact, best: Some(action)});
+        }
+        best
+    }
+
+    // Whether time is up, or a helper's main search has finished. The clock only counts once depth 1
+    // is done, so there's always an action to play.
+    fn out_of_time(&mut self) -> bool {
+        if !self.stopped && self.nodes.is_multiple_of(NODES_PER_CLOCK_CHECK) {
+            self.stopped = self.abort.as_ref().is_some_and(|abort| abort.load(Ordering::Relaxed)) ||
+                self.completed_depth > 0 && self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.stopped
+    }
+
+    fn negamax(&mut self, state: &mut GameState, depth: u32, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.out_of_time() {
+            // The result is thrown away, so any score will do
+            return 0;
+        }
+        match state.outcome() {
+            // Prefer quicker wins and slower losses
+            Some(Outcome::Win(winner, _)) => return if winner == state.turn() { WIN_SCORE - ply } else { ply - WIN_SCORE },
+            Some(Outcome::Draw(_)) => return 0,
+            None => {},
+        }
+        if depth == 0 {
+            return self.evaluator.evaluate(state);
+        }
+        let key = state.hash();
+        let entry = self.table.probe(key);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            let score = score_from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {},
+            }
+        }
+        // The side to move has at least one action, or the game would be over
+        let mut actions = state.legal_actions();
+        order_actions(&mut actions, entry.and_then(|entry| entry.best));
+        let original_alpha = alpha;
+        let mut best = (-INFINITY, actions[0]);
+        for action in actions {
+            let undo = state.make(action);
+            let score = -self.negamax(state, depth - 1, ply + 1, -beta, -alpha);
+            state.unmake(undo);
+            if self.stopped {
+                return 0;
+            }
+            if score > best.0 {
+                best = (score, action);
+            }
+            if score > alpha {
+                alpha = score;
+            }
+            if score >= beta {
+                break;
+            }
+        }
+        let (score, action) = best;
+        let bound = if score >= beta {
+            Bound::Lower
+        } else if score <= original_alpha {
+            Bound::Upper
+        } else {
+            Bound::Exact
+        };
+        self.table.store(TableEntry {key, depth, score: score_to_table(score, ply), bound, best: Some(action)});
+        score
+    }
+}
+
+// Tries the action the table remembers as best first, since it's the likeliest to cause a cutoff.
+fn order_actions(actions: &mut [Action], best: Option<Action>) {
+    if let Some(position) = best.and_then(|best| actions.iter().position(|&action| action == best)) {
+        actions[..=position].rotate_right(1);
+    }
+}
+
+// Win scores count plies from the root, but the table is shared between searches from different
+// roots, so it stores them counting from the position itself instead.
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_THRESHOLD {
+        score + ply
+    } else if score < -WIN_THRESHOLD {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_THRESHOLD {
+        score - ply
+    } else if score < -WIN_THRESHOLD {
+        score + ply
+    } else {
+        score
+    }
+}
@@ -0,0 +1,84 @@
+use std::io;
+use epic_not_chess_game::{Action, GameState, Square};
+
+pub const HELP: &str = "\
+Type a move like 3,2-5,4, an explosion like x2,1, or one of these commands:
+  help            Show this list
+  moves [r,f]     List every legal action, or just those of the piece on r,f
+  undo            Take back your last move
+  hint            Ask the engine what it would play
+  resign          Give up the game
+  save [file]     Save the game to carry on later
+  load [file]     Carry on with a saved game
+  flip            Turn the board around
+  quit            Stop playing without finishing the game";
+
+pub const DEFAULT_SAVE_FILE: &str = "saved_game.txt";
+
+// What a human can type at the move prompt, once it's been understood.
+pub enum Command {
+    Act(Action),
+    Undo,
+    Hint,
+    Resign,
+    Save(String),
+    Load(String),
+    Flip,
+    Quit,
+}
+
+pub fn read_line() -> String {
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line).expect("Failed to read line") == 0 {
+        // Standard input was closed, so nobody is left to play
+        std::process::exit(0);
+    }
+    line.trim().to_string()
+}
+
+// Prompts until the human types an action or a command the game loop has to deal with.
+// Anything that only needs the position, like help and moves, is answered here.
+pub fn read_command(state: &GameState) -> Command {
+    loop {
+        println!("Your move? (Type help for the commands.)");
+        let line = read_line();
+        let mut words = line.split_whitespace();
+        let path = |word: Option<&str>| word.unwrap_or(DEFAULT_SAVE_FILE).to_string();
+        match words.next() {
+            None => continue,
+            Some("help") => println!("{}", HELP),
+            Some("moves") => list_moves(state, words.next()),
+            Some("undo") => return Command::Undo,
+            Some("hint") => return Command::Hint,
+            Some("resign") => return Command::Resign,
+            Some("save") => return Command::Save(path(words.next())),
+            Some("load") => return Command::Load(path(words.next())),
+            Some("flip") => return Command::Flip,
+            Some("quit") => return Command::Quit,
+            Some(word) if word.chars().all(char::is_alphabetic) && word != "x" => {
+                println!("There's no `{}` command. Type help for the list, or a move like 3,2-5,4.", word);
+            },
+            Some(_) => match state.parse_action(&line) {
+                Ok(action) => return Command::Act(action),
+                Err(e) => println!("Can't play that: {}.", e),
+            },
+        }
+    }
+}
+
+fn list_moves(state: &GameState, square: Option<&str>) {
+    let actions = match square.map(Square::from_notation) {
+        None => state.legal_actions(),
+        Some(Ok(square)) if state.movable_piece_at(square).is_some() => state.legal_actions_from(square),
+        Some(Ok(square)) => {
+            println!("{} has no piece that can move on {},{}.", state.turn(), square.rank, square.file);
+            return;
+        },
+        Some(Err(e)) => {
+            println!("{}.", e);
+            return;
+        },
+    };
+    let notations = actions.iter().map(Action::to_notation).collect::<Vec<_>>();
+    println!("{} legal actions: {}", notations.len(), notations.join(" "));
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::sync::Mutex;
+use crate::piece::Action;
+
+/// How a stored score relates to the position's true value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound {
+    Exact,
+    Lower, // The search failed high, so the value is at least this
+    Upper, // The search failed low, so the value is at most this
+}
+
+/// What an earlier search found out about one position.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TableEntry {
+    pub key: u64,
+    pub depth: u32,
+    pub score: i32,
+    pub bound: Bound,
+    pub best: Option<Action>,
+}
+
+/// A fixed-size cache of search results indexed by Zobrist hash, so positions reached by different
+/// move orders aren't searched twice. When two positions want the same slot, the deeper search stays.
+/// Every slot has its own lock, so searches on several threads can share one table.
+pub struct TranspositionTable {
+    entries: Vec<Mutex<Option<TableEntry>>>,
+}
+
+impl TranspositionTable {
+    /// A table with room for `size` entries, rounded up to a power of two.
+    pub fn new(size: usize) -> TranspositionTable {
+        TranspositionTable {entries: (0..size.max(1).next_power_of_two()).map(|_| Mutex::new(None)).collect()}
+    }
+
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    // Entries are plain data, so one left behind by a thread that panicked is still fine to use
+    fn lock(&self, slot: usize) -> std::sync::MutexGuard<'_, Option<TableEntry>> {
+        self.entries[slot].lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        self.lock(self.slot(key)).filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&self, entry: TableEntry) {
+        let mut slot = self.lock(self.slot(entry.key));
+        match *slot {
+            Some(old) if old.key != entry.key && old.depth > entry.depth => {},
+            _ => *slot = Some(entry),
+        }
+    }
+
+    pub fn clear(&self) {
+        for slot in 0..self.entries.len() {
+            *self.lock(slot) = None;
+        }
+    }
+}
+
+impl fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let used = (0..self.entries.len()).filter(|&slot| self.lock(slot).is_some()).count();
+        write!(f, "TranspositionTable {{ {} of {} entries used }}", used, self.entries.len())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::bitboard::index;
+use crate::notation::MAX_HEALTH;
+use crate::piece::{Color, Piece, BOARD_SIZE};
+
+// Zobrist hashing: every (colour, kind, health, square) a piece can be in gets a fixed random key,
+// and a position's hash is all its pieces' keys XORed together, plus one more if Blue is to move.
+// Changing one piece only takes XORing out its old key and XORing in the new one.
+
+const HEALTHS: usize = MAX_HEALTH as usize + 1;
+const PIECE_KEYS: usize = 3 * 3 * HEALTHS * BOARD_SIZE * BOARD_SIZE;
+
+// SplitMix64, which is plenty random for this and can run at compile time.
+const fn keys() -> [u64; PIECE_KEYS + 1] {
+    let mut keys = [0; PIECE_KEYS + 1];
+    let mut state: u64 = 0x0b5e_55ed_c0ff_ee00;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+static KEYS: [u64; PIECE_KEYS + 1] = keys();
+
+pub(crate) fn piece_key(piece: &Piece) -> u64 {
+    let health = (piece.health.max(0) as usize).min(HEALTHS - 1);
+    KEYS[((piece.color as usize * 3 + piece.kind as usize) * HEALTHS + health) * BOARD_SIZE * BOARD_SIZE + index(piece.pos)]
+}
+
+pub(crate) fn turn_key(turn: Color) -> u64 {
+    // The last key is for Blue to move
+    if turn == Color::Blue { KEYS[PIECE_KEYS] } else { 0 }
+}
+
+pub(crate) fn hash(pieces: &[Piece], turn: Color) -> u64 {
+    pieces.iter().fold(turn_key(turn), |hash, piece| hash ^ piece_key(piece))
+}
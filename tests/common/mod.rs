@@ -0,0 +1,40 @@
+// Setup shared by the integration tests. Not every test file uses every helper.
+#![allow(dead_code)]
+
+use epic_not_chess_game::{Action, GameState};
+
+pub fn position(notation: &str) -> GameState {
+    GameState::from_notation(notation).unwrap()
+}
+
+/// The action written as `notation`, checking the side to move may play it.
+pub fn legal_action(state: &GameState, notation: &str) -> Action {
+    let action = Action::from_notation(notation).unwrap();
+    assert!(state.legal_actions().contains(&action), "{} isn't legal", notation);
+    action
+}
+
+pub fn play(state: &mut GameState, actions: &[&str]) {
+    for action in actions {
+        let action = legal_action(state, action);
+        state.apply(action);
+    }
+}
+
+/// The hash of the same position read back from its notation, worked out from scratch.
+pub fn fresh_hash(state: &GameState) -> u64 {
+    position(&state.to_notation()).hash()
+}
+
+/// Makes and unmakes every legal action from `state`, checking the hash after each make and
+/// that each unmake puts everything back exactly.
+pub fn check_every_action(state: &mut GameState) {
+    let before = state.clone();
+    assert_eq!(state.hash(), fresh_hash(state));
+    for action in state.legal_actions() {
+        let undo = state.make(action);
+        assert_eq!(state.hash(), fresh_hash(state), "after {}", action.to_notation());
+        state.unmake(undo);
+        assert_eq!(*state, before, "after taking back {}", action.to_notation());
+    }
+}
@@ -0,0 +1,42 @@
+// The draw rules: threefold repetition and the no-progress limit.
+
+mod common;
+
+use common::{play, position};
+use epic_not_chess_game::{Action, DrawReason, Outcome};
+
+#[test]
+fn threefold_repetition_draws() {
+    let mut state = position("r2@00,b2@99 r");
+    play(&mut state, &["0,0-0,1", "9,9-9,8", "0,1-0,0", "9,8-9,9"]);
+    assert_eq!(state.repetitions(), 2);
+    assert_eq!(state.outcome(), None);
+    play(&mut state, &["0,0-0,1", "9,9-9,8", "0,1-0,0", "9,8-9,9"]);
+    assert_eq!(state.repetitions(), 3);
+    assert_eq!(state.outcome(), Some(Outcome::Draw(DrawReason::Repetition)));
+}
+
+#[test]
+fn no_progress_draws_after_the_limit() {
+    let mut state = position("r2@00,b2@99 r");
+    state.set_no_progress_limit(Some(3));
+    play(&mut state, &["0,0-1,0", "9,9-8,9"]);
+    assert_eq!(state.outcome(), None);
+    play(&mut state, &["1,0-2,0"]);
+    assert_eq!(state.quiet_plies(), 3);
+    assert_eq!(state.outcome(), Some(Outcome::Draw(DrawReason::NoProgress)));
+    state.set_no_progress_limit(None);
+    assert_eq!(state.outcome(), None);
+}
+
+#[test]
+fn damage_resets_the_no_progress_count() {
+    let mut state = position("r3@00,b3@22,b2@99 r");
+    play(&mut state, &["0,0-1,1", "9,9-9,8"]);
+    assert_eq!(state.quiet_plies(), 2);
+    let undo = state.make(Action::from_notation("1,1-2,2").unwrap());
+    assert_eq!(state.quiet_plies(), 0);
+    state.unmake(undo);
+    assert_eq!(state.quiet_plies(), 2);
+    assert_eq!(state.repetitions(), 1);
+}
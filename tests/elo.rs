@@ -0,0 +1,22 @@
+// Elo estimates for match results.
+
+use epic_not_chess_game::elo_summary;
+
+#[test]
+fn one_sided_matches_are_unbounded() {
+    assert_eq!(elo_summary(4, 0, 0), "Score 100.0%, so the Elo difference is unbounded; play more games or closer opponents.");
+    assert_eq!(elo_summary(0, 0, 4), "Score 0.0%, so the Elo difference is unbounded; play more games or closer opponents.");
+    assert_eq!(elo_summary(0, 0, 0), "No games were played.");
+}
+
+#[test]
+fn all_draws_give_no_interval() {
+    assert_eq!(elo_summary(0, 4, 0), "Score 50.0%, Elo difference +0 (every game was drawn, so there's no confidence interval; play more games)");
+}
+
+#[test]
+fn mixed_results_give_an_interval() {
+    // 7.5 out of 10 is +191, with the interval from the spread of wins, draws and losses
+    assert_eq!(elo_summary(6, 3, 1), "Score 75.0%, Elo difference +191 (95% confidence: +29 to +543)");
+    assert_eq!(elo_summary(1, 3, 6), "Score 25.0%, Elo difference -191 (95% confidence: -543 to -29)");
+}
@@ -0,0 +1,50 @@
+// The evaluator's config file and the scores it gives.
+
+mod common;
+
+use common::position;
+use epic_not_chess_game::{ConfigError, Evaluator, GameState, Weights};
+
+fn syntax_error(config: &str) -> (usize, String) {
+    match Evaluator::from_config(config) {
+        Err(ConfigError::Syntax {line, message}) => (line, message),
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn config_sets_the_weights_it_names() {
+    let config = "# Tuned by hand\n\nnumber_health = 90\n  mobility=7   # more than usual\n\n";
+    let evaluator = Evaluator::from_config(config).unwrap();
+    assert_eq!(evaluator.weights, Weights {number_health: 90, mobility: 7, ..Weights::default()});
+    assert_eq!(Evaluator::from_config("").unwrap(), Evaluator::default());
+    assert_eq!(Evaluator::from_config("explosion_threat = -5").unwrap().weights.explosion_threat, -5);
+}
+
+#[test]
+fn config_errors_say_which_line() {
+    let (line, message) = syntax_error("mobility = 3\nbig_health = lots");
+    assert_eq!(line, 2);
+    assert!(message.contains("`lots` isn't a whole number"));
+    let (line, message) = syntax_error("\n# nothing yet\nspeed = 3");
+    assert_eq!(line, 3);
+    assert!(message.contains("unknown weight `speed`"));
+    let (line, message) = syntax_error("goal_distance 5");
+    assert_eq!(line, 1);
+    assert!(message.contains("expected `name = value`"));
+    assert!(syntax_error("mobility = 2.5").1.contains("isn't a whole number"));
+}
+
+#[test]
+fn the_score_flips_with_the_side_to_move() {
+    let evaluator = Evaluator::default();
+    for pieces in [
+        "rX@04,rX@05,rB4@40,r1@45,b1@55,bB4@50,bX@94,bX@95",
+        "rX@04,rX@05,r3@83,r5@85,b2@86,g1@44,bX@94,bX@95",
+    ] {
+        let red = position(&format!("{} r", pieces));
+        let blue = position(&format!("{} b", pieces));
+        assert_eq!(evaluator.evaluate(&red), -evaluator.evaluate(&blue), "{}", pieces);
+    }
+    assert_eq!(evaluator.evaluate(&GameState::new()), 0);
+}
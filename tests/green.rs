@@ -0,0 +1,64 @@
+// Green, the neutral side the rules move at the end of every round.
+
+mod common;
+
+use common::{play, position};
+use epic_not_chess_game::{resolve_action, Action, Color, GameState, Outcome, WinReason};
+
+// The position's pieces, leaving off the side to move and the counts.
+fn pieces(state: &GameState) -> String {
+    state.to_notation().split(' ').next().unwrap().to_string()
+}
+
+#[test]
+fn green_moves_once_blue_has_played() {
+    let mut state = position("r2@00,b2@99,g1@44 r");
+    play(&mut state, &["0,0-0,1"]);
+    assert_eq!(pieces(&state), "r2@01,b2@99,g1@44");
+    // Red on 0,1 is nearer than Blue, and 3,4 comes before 4,3 on the board
+    play(&mut state, &["9,9-9,8"]);
+    assert_eq!(pieces(&state), "r2@01,b2@98,g1@34");
+}
+
+#[test]
+fn green_fights_what_it_steps_onto() {
+    let mut state = position("r3@00,b2@99,g1@10 b 7 5");
+    let report = resolve_action(&mut state, Action::from_notation("9,9-9,8").unwrap());
+    assert_eq!(pieces(&state), "r2@00,b2@98");
+    assert_eq!(state.quiet_plies(), 0);
+    assert_eq!(report.neutral.len(), 1);
+    assert_eq!(report.neutral[0].damaged.len(), 2);
+    assert_eq!(report.neutral[0].mover_end, None);
+}
+
+#[test]
+fn green_stays_put_once_blue_has_won() {
+    // Without the rule, the 1 on 0,6 would step onto 0,5 and knock Blue's piece off the goal
+    let mut state = position("rX@04,rX@05,r2@50,b2@04,b1@15,g1@06,bX@94,bX@95 b");
+    play(&mut state, &["1,5-0,5"]);
+    assert_eq!(pieces(&state), "rX@04,rX@05,r2@50,b2@04,b1@05,g1@06,bX@94,bX@95");
+    assert_eq!(state.outcome(), Some(Outcome::Win(Color::Blue, WinReason::Goals)));
+}
+
+#[test]
+fn lower_squares_go_first_and_green_never_steps_on_green() {
+    // 0,0 is boxed in by the others when its go comes, so it stays put
+    let mut state = position("r2@50,b2@59,g1@00,g1@01,g1@10 b");
+    play(&mut state, &["5,9-6,9"]);
+    assert_eq!(pieces(&state), "r2@50,b2@69,g1@00,g1@11,g1@20");
+    // Green Bs don't move
+    let mut state = position("r2@50,b2@59,gB3@00 b");
+    play(&mut state, &["5,9-6,9"]);
+    assert_eq!(pieces(&state), "r2@50,b2@69,gB3@00");
+}
+
+#[test]
+fn unmake_takes_back_greens_moves_too() {
+    let start = position("r3@00,rB4@21,b2@99,g1@10,g1@44,g1@45 b 3");
+    let mut state = start.clone();
+    let undo = state.make(Action::from_notation("9,9-9,8").unwrap());
+    assert_ne!(pieces(&state), pieces(&start));
+    assert_eq!(state.hash(), position(&state.to_notation()).hash());
+    state.unmake(undo);
+    assert_eq!(state, start);
+}
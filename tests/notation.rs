@@ -0,0 +1,91 @@
+// Reading and writing positions in position notation, and reading actions as players type them.
+
+use epic_not_chess_game::{Color, GameState};
+
+fn round_trip(state: &GameState) {
+    assert_eq!(GameState::from_notation(&state.to_notation()).as_ref(), Ok(state));
+}
+
+fn error(notation: &str) -> String {
+    GameState::from_notation(notation).unwrap_err().to_string()
+}
+
+#[test]
+fn positions_survive_a_round_trip() {
+    round_trip(&GameState::new());
+    round_trip(&GameState::from_notation("rB4@21,gB2@44,b1@90,rX@04 b").unwrap());
+    round_trip(&GameState::from_notation("r5@00,b3@99 r 17").unwrap());
+    round_trip(&GameState::from_notation("r5@00,b3@99 b 18 6").unwrap());
+}
+
+#[test]
+fn notation_reads_what_it_says() {
+    let state = GameState::from_notation("rB4@21,g1@44,bX@94 b 7 3").unwrap();
+    assert_eq!(state.pieces().len(), 3);
+    assert_eq!(state.turn(), Color::Blue);
+    assert_eq!(state.ply(), 7);
+    assert_eq!(state.quiet_plies(), 3);
+    assert_eq!(state.to_notation(), "rB4@21,g1@44,bX@94 b 7 3");
+    assert_eq!(GameState::from_notation("r2@00 r").unwrap().to_notation(), "r2@00 r 0");
+}
+
+#[test]
+fn bad_pieces_are_rejected() {
+    assert!(error("rB4@99 r").contains("doesn't fit on the board"));
+    assert!(error("rB3@09 r").contains("doesn't fit on the board"));
+    assert!(error("r6@00 r").contains("health has to be from 1 to 5"));
+    assert!(error("r0@00 r").contains("health has to be from 1 to 5"));
+    assert!(error("rB9@00 r").contains("health has to be from 1 to 5"));
+    assert!(error("y2@00 r").contains("isn't a colour"));
+    assert!(error("r2@0 r").contains("rank digit then a file digit"));
+    assert!(error("r2 r").contains("expected something like r3@12"));
+}
+
+#[test]
+fn overlapping_pieces_are_rejected() {
+    assert!(error("r2@00,r3@00 r").contains("overlap"));
+    assert!(error("rB4@21,r2@32 r").contains("overlap"));
+    // Goals don't get in anyone's way
+    assert!(GameState::from_notation("rX@04,r2@04 r").is_ok());
+}
+
+#[test]
+fn the_side_to_move_has_to_be_red_or_blue() {
+    assert!(error("r2@00 g").contains("has to be r or b"));
+    assert!(error("r2@00 rb").contains("has to be r or b"));
+    assert!(error("r2@00 x").contains("isn't a colour"));
+}
+
+#[test]
+fn bad_fields_are_rejected() {
+    assert!(error("r2@00").contains("expected the pieces, the side to move"));
+    assert!(error("r2@00 r 1 0 5").contains("expected the pieces, the side to move"));
+    assert!(error("r2@00 r ten").contains("isn't a ply count"));
+    assert!(error("r2@00 r 10 x").contains("isn't a quiet ply count"));
+}
+
+#[test]
+fn a_b_can_be_moved_by_any_corner() {
+    let state = GameState::from_notation("rB4@21,r2@00,b2@99 r").unwrap();
+    let parse = |input: &str| state.parse_action(input).map(|action| action.to_notation());
+    assert_eq!(parse("2,1-1,1"), Ok("2,1-1,1".to_string()));
+    // The top right corner moving down a rank takes the whole B down a rank
+    assert_eq!(parse("3,2-2,2"), Ok("2,1-1,1".to_string()));
+    assert_eq!(parse("3,2-3,3"), Ok("2,1-2,2".to_string()));
+    assert_eq!(parse("x3,2"), Ok("x2,1".to_string()));
+    let state = GameState::from_notation("rB4@00,b2@99 r").unwrap();
+    assert!(state.parse_action("1,1-0,1").unwrap_err().to_string().contains("isn't legal"));
+}
+
+#[test]
+fn typed_actions_have_to_be_clear_and_legal() {
+    let state = GameState::from_notation("rB4@21,r2@00,r1@09,r2@19,b2@99 r").unwrap();
+    let error = |input: &str| state.parse_action(input).unwrap_err().to_string();
+    assert_eq!(state.parse_action("0,9").map(|action| action.to_notation()), Ok("0,9-0,8".to_string()));
+    assert_eq!(error("0,0"), "0,0 is ambiguous; that piece could play any of 0,0-0,1 0,0-1,0");
+    assert_eq!(error("9,9-9,8"), "the piece on 9,9 is Blue's, not Red's");
+    assert_eq!(error("5,5-5,6"), "Red has no piece on 5,5");
+    assert_eq!(error("x0,0"), "only a B can explode, and the piece on 0,0 isn't one");
+    assert_eq!(error("0,0-2,0"), "0,0-2,0 isn't legal; that piece can play 0,0-0,1 0,0-1,0");
+    assert!(error("fly").contains("isn't an action"));
+}
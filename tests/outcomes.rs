@@ -0,0 +1,46 @@
+// How games are won: by reaching the goals, or by the other side running out of legal actions.
+
+mod common;
+
+use common::{play, position};
+use epic_not_chess_game::{Color, Outcome, WinReason};
+
+#[test]
+fn covering_both_enemy_goals_wins() {
+    assert_eq!(position("rX@04,rX@05,r2@94,r2@95,b2@50,bX@94,bX@95 b").outcome(), Some(Outcome::Win(Color::Red, WinReason::Goals)));
+    assert_eq!(position("rX@04,rX@05,b2@04,b2@05,r2@50,bX@94,bX@95 r").outcome(), Some(Outcome::Win(Color::Blue, WinReason::Goals)));
+    // One goal isn't enough
+    assert_eq!(position("rX@04,rX@05,r2@94,b2@50,bX@94,bX@95 b").outcome(), None);
+    // A B covers both goals at once
+    assert_eq!(position("rX@04,rX@05,rB4@84,b2@50,bX@94,bX@95 b").outcome(), Some(Outcome::Win(Color::Red, WinReason::Goals)));
+}
+
+#[test]
+fn having_no_legal_actions_loses() {
+    // Red only has goals left, which can't move
+    let state = position("rX@04,rX@05,b2@50,bX@94,bX@95 r");
+    assert!(!state.has_legal_actions());
+    assert!(state.legal_actions().is_empty());
+    assert_eq!(state.outcome(), Some(Outcome::Win(Color::Blue, WinReason::NoLegalActions)));
+    // The same for Blue, and for a side with no pieces at all
+    assert_eq!(position("rX@04,rX@05,r2@50,bX@94,bX@95 b").outcome(), Some(Outcome::Win(Color::Red, WinReason::NoLegalActions)));
+    assert_eq!(position("g1@44 r").outcome(), Some(Outcome::Win(Color::Blue, WinReason::NoLegalActions)));
+    // A 4 only ever lands on squares of its own colour on the checkerboard, so 4s filling every
+    // one of those squares have nowhere to go
+    let fours = (0..100).filter(|i| (i / 10 + i % 10) % 2 == 0).map(|i| format!("b4@{:02}", i)).collect::<Vec<_>>();
+    let state = position(&format!("{},r2@54 b", fours.join(",")));
+    assert!(!state.has_legal_actions());
+    assert_eq!(state.outcome(), Some(Outcome::Win(Color::Red, WinReason::NoLegalActions)));
+    // Reaching the goals still counts first, even when the loser is stuck too
+    let state = position("rX@04,rX@05,r2@94,r2@95,bX@94,bX@95 b");
+    assert!(!state.has_legal_actions());
+    assert_eq!(state.outcome(), Some(Outcome::Win(Color::Red, WinReason::Goals)));
+}
+
+#[test]
+fn the_game_ends_when_the_last_movable_piece_dies() {
+    // Red's 1 walks into Blue's only 1 and both die, leaving Blue nothing that can move
+    let mut state = position("rX@04,rX@05,r2@50,r1@00,b1@01,bX@94,bX@95 r");
+    play(&mut state, &["0,0-0,1"]);
+    assert_eq!(state.outcome(), Some(Outcome::Win(Color::Red, WinReason::NoLegalActions)));
+}
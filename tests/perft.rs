@@ -0,0 +1,72 @@
+// Known perft counts. If a rule change moves any of these, check it was meant to before updating them.
+
+use epic_not_chess_game::{divide, perft, GameState};
+
+fn counts(position: &str, depth: u32) -> Vec<u64> {
+    let mut state = GameState::from_notation(position).unwrap();
+    (1..=depth).map(|depth| perft(&mut state, depth)).collect()
+}
+
+#[test]
+fn start_position() {
+    assert_eq!(counts("r1@00,r2@01,r3@02,r5@03,rX@04,rX@05,r5@06,r3@07,r2@08,r1@09,r3@10,r2@11,r2@12,r2@17,r2@18,r3@19,rB4@21,rB4@27,\
+        g1@44,g1@45,g1@54,g1@55,b1@90,b2@91,b3@92,b5@93,bX@94,bX@95,b5@96,b3@97,b2@98,b1@99,b3@80,b2@81,b2@82,b2@87,b2@88,b3@89,bB4@61,bB4@67 r", 3),
+        [30, 900, 30960]);
+    assert_eq!(perft(&mut GameState::new(), 2), 900);
+}
+
+#[test]
+fn health_one_pieces_only_go_forward_or_sideways() {
+    assert_eq!(counts("r1@00 r", 1), [2]);
+    assert_eq!(counts("b1@00 b", 1), [1]);
+    // Green is never the side to move, so Red with only Green on the board has nothing to do
+    assert_eq!(counts("g1@44 r", 1), [0]);
+}
+
+#[test]
+fn numbers_move_by_health() {
+    assert_eq!(counts("r5@00 r", 1), [8]);
+    assert_eq!(counts("r4@00 r", 1), [4]);
+    assert_eq!(counts("r5@44 r", 1), [24]);
+    assert_eq!(counts("r4@44 r", 1), [12]);
+    assert_eq!(counts("r3@44 r", 1), [8]);
+    assert_eq!(counts("r2@44 r", 1), [4]);
+}
+
+#[test]
+fn bigs_stay_on_the_board() {
+    // Each corner leaves two moves and the explosion
+    assert_eq!(counts("rB4@00 r", 1), [3]);
+    assert_eq!(counts("rB4@08 r", 1), [3]);
+    assert_eq!(counts("rB4@80 r", 1), [3]);
+    assert_eq!(counts("rB4@88 r", 1), [3]);
+    assert_eq!(counts("rB4@44 r", 1), [5]);
+}
+
+#[test]
+fn only_own_pieces_block() {
+    assert_eq!(counts("r2@00,r2@01 r", 1), [3]);
+    assert_eq!(counts("r2@00,b2@01 r", 4), [2, 3, 9, 21]);
+    assert_eq!(counts("r1@09,rB3@18,b1@90,bB2@70,g1@44,g1@45,g1@54,g1@55,rX@04,rX@05,bX@94,bX@95 b", 4), [4, 16, 72, 324]);
+}
+
+#[test]
+fn explosions_and_fights() {
+    assert_eq!(counts("rX@04,rX@05,rB4@40,r1@45,b1@55,bB4@50,bX@94,bX@95 r", 4), [7, 38, 162, 775]);
+}
+
+#[test]
+fn won_games_end() {
+    assert_eq!(counts("rX@04,rX@05,r3@83,r5@85,b2@86,bX@94,bX@95 r", 4), [26, 100, 2550, 8625]);
+    // Red already covers both of Blue's goals
+    assert_eq!(counts("rX@04,rX@05,r2@94,r2@95,b2@50,bX@94,bX@95 b", 2), [0, 0]);
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let mut state = GameState::new();
+    let split = divide(&mut state, 3);
+    assert_eq!(split.len(), 30);
+    assert_eq!(split.iter().map(|&(_, leaves)| leaves).sum::<u64>(), perft(&mut state, 3));
+    assert_eq!(state, GameState::new());
+}
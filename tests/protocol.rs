@@ -0,0 +1,75 @@
+// The engine protocol, driven one line at a time.
+
+use epic_not_chess_game::{Engine, GameState, ENGINE_NAME};
+
+#[test]
+fn handshake() {
+    let mut engine = Engine::default();
+    let lines = engine.handle("uci");
+    assert_eq!(lines.first().unwrap(), &format!("id name {}", ENGINE_NAME));
+    assert_eq!(lines.last().unwrap(), "uciok");
+    assert_eq!(engine.handle("isready"), ["readyok"]);
+    assert!(engine.handle("ucinewgame").is_empty());
+    assert!(engine.handle("").is_empty());
+    assert_eq!(engine.handle("fly"), ["info string error: unknown command `fly`"]);
+    assert!(!engine.has_quit());
+    assert!(engine.handle("quit").is_empty());
+    assert!(engine.has_quit());
+}
+
+#[test]
+fn position_plays_the_moves_given() {
+    let mut engine = Engine::default();
+    assert!(engine.handle("position startpos moves 1,0-2,0 8,0-7,0").is_empty());
+    assert_eq!(engine.state().ply(), 2);
+    assert!(engine.handle("position r2@00,b2@99 b 5 moves 9,9-9,8").is_empty());
+    assert_eq!(engine.state().to_notation(), "r2@00,b2@98 r 6 1");
+}
+
+#[test]
+fn bad_positions_leave_the_old_one_alone() {
+    let mut engine = Engine::default();
+    engine.handle("position startpos moves 1,0-2,0");
+    let before = engine.state().clone();
+    assert_eq!(engine.handle("position startpos moves 1,0-2,0 8,1-7,1"), ["info string error: 8,1-7,1 isn't legal in that position"]);
+    assert_eq!(engine.handle("position startpos moves 1,0"), ["info string error: `1,0` isn't an action; write a move like 3,2-5,4 or an explosion like x2,1"]);
+    assert!(engine.handle("position r9@00 r")[0].starts_with("info string error: bad piece"));
+    assert_eq!(engine.handle("position"), ["info string error: position needs startpos or a position in notation"]);
+    assert_eq!(engine.state(), &before);
+}
+
+#[test]
+fn setoption_checks_its_option() {
+    let mut engine = Engine::default();
+    assert!(engine.handle("setoption name Threads value 2").is_empty());
+    assert_eq!(engine.handle("setoption name Threads value 0"), ["info string error: Threads has to be a positive number, not `0`"]);
+    assert_eq!(engine.handle("setoption name Hash value 64"), ["info string error: there's no option called Hash"]);
+}
+
+#[test]
+fn go_replies_with_info_then_bestmove() {
+    let mut engine = Engine::default();
+    engine.handle("position startpos");
+    let lines = engine.handle("go depth 2");
+    assert_eq!(lines.len(), 2);
+    let info = lines[0].split(' ').collect::<Vec<_>>();
+    assert_eq!(info[..3], ["info", "depth", "2"]);
+    assert_eq!(info[3], "score");
+    assert!(info[4] == "cp" || info[4] == "mate");
+    assert_eq!([info[6], info[8], info[10]], ["nodes", "time", "pv"]);
+    let best = lines[1].strip_prefix("bestmove ").unwrap();
+    assert_eq!(info[11], best);
+    assert!(GameState::new().parse_action(best).is_ok());
+    assert_eq!(engine.handle("go depth"), ["info string error: depth needs a number"]);
+    assert_eq!(engine.handle("go faster"), ["info string error: go doesn't understand `faster`"]);
+}
+
+#[test]
+fn go_in_a_finished_game_has_nothing_to_play() {
+    let mut engine = Engine::default();
+    // Red already covers both of Blue's goals
+    engine.handle("position rX@04,rX@05,r2@94,r2@95,b2@50,bX@94,bX@95 b");
+    assert_eq!(engine.handle("go depth 2"), ["bestmove none"]);
+    engine.handle("position rX@04,rX@05,b2@50,bX@94,bX@95 r");
+    assert_eq!(engine.handle("go movetime 10"), ["bestmove none"]);
+}
@@ -0,0 +1,74 @@
+// Writing game records out and reading them back.
+
+mod common;
+
+use common::{legal_action, play, position};
+use epic_not_chess_game::{GameRecord, GameState, RecordError};
+
+fn record_of(start: GameState, actions: &[&str]) -> GameRecord {
+    let mut record = GameRecord::new(start.clone());
+    let mut state = start;
+    for action in actions {
+        let action = legal_action(&state, action);
+        state.apply(action);
+        record.push(action);
+    }
+    record
+}
+
+fn syntax_error(text: &str) -> String {
+    match GameRecord::parse(text) {
+        Err(RecordError::Syntax(message)) => message,
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+#[test]
+fn records_survive_a_round_trip() {
+    let mut record = record_of(GameState::new(), &["1,0-2,0", "8,0-7,0", "x2,7"]);
+    record.set_header("Red", "human");
+    let text = record.to_text();
+    assert_eq!(text, "[Red \"human\"]\n\n1. 1,0-2,0 8,0-7,0 2. x2,7 *\n");
+    assert_eq!(GameRecord::parse(&text).unwrap(), record);
+}
+
+#[test]
+fn a_game_blue_starts_is_numbered_from_blue() {
+    let record = record_of(position("r2@00,b2@99 b 5"), &["9,9-9,8", "0,0-0,1", "9,8-8,8"]);
+    let text = record.to_text();
+    assert_eq!(text, "[Position \"r2@00,b2@99 b 5\"]\n\n3... 9,9-9,8 4. 0,0-0,1 9,8-8,8 *\n");
+    let parsed = GameRecord::parse(&text).unwrap();
+    assert_eq!(parsed.start(), record.start());
+    assert_eq!(parsed.actions(), record.actions());
+    assert_eq!(parsed.to_text(), text);
+}
+
+#[test]
+fn illegal_actions_are_rejected() {
+    assert_eq!(syntax_error("1. 1,1-3,1 *"), "1,1-3,1 isn't legal after 0 actions");
+    // Red's 8,5-9,5 covers both of Blue's goals, so Blue doesn't get another go
+    let text = "[Position \"rX@04,rX@05,r2@94,r2@85,b2@50,bX@94,bX@95 r\"]\n\n1. 8,5-9,5 5,0-4,0 *";
+    assert_eq!(syntax_error(text), "5,0-4,0 isn't legal after 1 actions");
+    assert!(syntax_error("[Red \"human\"\n\n*").contains("unfinished header"));
+}
+
+#[test]
+fn the_result_header_wins_over_the_position() {
+    let mut state = position("rX@04,rX@05,r2@94,r2@85,b2@50,bX@94,bX@95 r");
+    let mut record = record_of(state.clone(), &["8,5-9,5"]);
+    play(&mut state, &["8,5-9,5"]);
+    assert!(state.winner().is_some());
+    assert!(record.to_text().ends_with(" 1-0\n"));
+    record.set_header("Result", "0-1");
+    assert!(record.to_text().ends_with(" 0-1\n"));
+    assert!(record_of(GameState::new(), &["1,0-2,0"]).to_text().ends_with(" *\n"));
+}
+
+#[test]
+fn quotes_in_header_values_survive() {
+    let mut record = GameRecord::new(GameState::new());
+    record.set_header("Event", "The \"big\" match, C:\\games");
+    let text = record.to_text();
+    assert!(text.starts_with("[Event \"The \\\"big\\\" match, C:\\\\games\"]\n"));
+    assert_eq!(GameRecord::parse(&text).unwrap().header("Event"), Some("The \"big\" match, C:\\games"));
+}
@@ -0,0 +1,68 @@
+// Drawing the board, especially Bs up against its edges, from each side.
+
+mod common;
+
+use common::position;
+use epic_not_chess_game::{render, Perspective, Square, BOARD_SIZE};
+
+fn board(lines: &[&str]) -> String {
+    lines.join("\n")
+}
+
+#[test]
+fn bs_in_the_corners_from_every_side() {
+    let state = position("rB4@00,bB3@88,r2@09,b1@90 r");
+    assert_eq!(render(state.pieces(), Perspective::Red, false), board(&[
+        "9 1       ╔╗",
+        "8         ╚╝",
+        "7           ",
+        "6           ",
+        "5           ",
+        "4           ",
+        "3           ",
+        "2           ",
+        "1 ╔╗        ",
+        "0 ╚╝       2",
+        "rf0123456789",
+    ]));
+    assert_eq!(render(state.pieces(), Perspective::Blue, false), board(&[
+        "0 2       ╔╗",
+        "1         ╚╝",
+        "2           ",
+        "3           ",
+        "4           ",
+        "5           ",
+        "6           ",
+        "7           ",
+        "8 ╔╗        ",
+        "9 ╚╝       1",
+        "rf9876543210",
+    ]));
+    assert_eq!(render(state.pieces(), Perspective::Spectator, false), board(&[
+        "0 ╔╗       1",
+        "1 ╚╝        ",
+        "2           ",
+        "3           ",
+        "4           ",
+        "5           ",
+        "6           ",
+        "7           ",
+        "8         ╔╗",
+        "9 2       ╚╝",
+        "fr0123456789",
+    ]));
+}
+
+#[test]
+fn square_at_undoes_to_screen() {
+    for perspective in [Perspective::Red, Perspective::Blue, Perspective::Spectator] {
+        for rank in 0..BOARD_SIZE {
+            for file in 0..BOARD_SIZE {
+                let square = Square {rank, file};
+                let (row, column) = perspective.to_screen(square);
+                assert!(row < BOARD_SIZE && column < BOARD_SIZE);
+                assert_eq!(perspective.square_at(row, column), square, "{:?}", perspective);
+            }
+        }
+    }
+}
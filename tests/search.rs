@@ -0,0 +1,66 @@
+// What the alpha-beta search actually plays.
+
+mod common;
+
+use common::{legal_action, position};
+use epic_not_chess_game::{AlphaBeta, Evaluator, GameState, Outcome, WIN_SCORE};
+
+// Plain negamax with no pruning and no table, scoring wins and draws the way the search does.
+fn minimax(state: &mut GameState, depth: u32, ply: i32, evaluator: &Evaluator) -> i32 {
+    match state.outcome() {
+        Some(Outcome::Win(winner, _)) => return if winner == state.turn() { WIN_SCORE - ply } else { ply - WIN_SCORE },
+        Some(Outcome::Draw(_)) => return 0,
+        None => {},
+    }
+    if depth == 0 {
+        return evaluator.evaluate(state);
+    }
+    state.legal_actions().into_iter().map(|action| {
+        let undo = state.make(action);
+        let score = -minimax(state, depth - 1, ply + 1, evaluator);
+        state.unmake(undo);
+        score
+    }).max().unwrap()
+}
+
+#[test]
+fn plays_a_goal_win_in_one() {
+    let state = position("rX@04,rX@05,r2@94,r2@85,b2@50,bX@94,bX@95 r");
+    let (action, score) = AlphaBeta::new(3).best_action(&state).unwrap();
+    assert_eq!(action, legal_action(&state, "8,5-9,5"));
+    assert_eq!(score, WIN_SCORE - 1);
+}
+
+#[test]
+fn does_not_hand_over_a_win() {
+    // Red's 2 on 0,5 keeps Blue off that goal; stepping aside to 0,6 lets 1,5-0,5 win for Blue
+    let state = position("rX@04,rX@05,r2@05,r2@50,b2@04,b2@15,bX@94,bX@95 r");
+    let (action, score) = AlphaBeta::new(2).best_action(&state).unwrap();
+    assert_ne!(action, legal_action(&state, "0,5-0,6"));
+    assert!(score > -WIN_SCORE / 2);
+    let mut losing = state.clone();
+    losing.apply(legal_action(&state, "0,5-0,6"));
+    assert_eq!(AlphaBeta::new(1).best_action(&losing).unwrap().1, WIN_SCORE - 1);
+}
+
+#[test]
+fn no_legal_actions_means_no_action() {
+    assert_eq!(AlphaBeta::new(3).best_action(&position("rX@04,rX@05,b2@50,bX@94,bX@95 r")), None);
+}
+
+#[test]
+fn the_table_does_not_change_the_score() {
+    let evaluator = Evaluator::default();
+    for (notation, depth) in [
+        ("rX@04,rX@05,rB4@40,r1@45,b1@55,bB4@50,bX@94,bX@95 r", 4),
+        ("rX@04,rX@05,r3@83,r5@85,b2@86,bX@94,bX@95 r", 3),
+        ("r1@09,rB3@18,b1@90,bB2@70,g1@44,g1@45,g1@54,g1@55,rX@04,rX@05,bX@94,bX@95 b", 4),
+    ] {
+        let mut state = position(notation);
+        let expected = minimax(&mut state, depth, 0, &evaluator);
+        for table_size in [1, 1 << 16] {
+            let (_, score) = AlphaBeta::new(depth).with_table_size(table_size).best_action(&state).unwrap();
+            assert_eq!(score, expected, "{} at depth {} with {} table entries", notation, depth, table_size);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Making and unmaking actions has to put every position back exactly as it was.
+
+mod common;
+
+use common::{check_every_action, legal_action, position};
+use epic_not_chess_game::{Action, GameState};
+
+#[test]
+fn unmake_restores_every_action_from_the_start() {
+    check_every_action(&mut GameState::new());
+}
+
+#[test]
+fn unmake_restores_explosions() {
+    // The 1 on 1,1 dies, the 3 on 3,0 is hurt and Blue's B takes two hits, which kills it
+    let mut state = position("rB4@21,r2@00,b3@30,b1@11,bB2@33,bX@94,bX@95,rX@04,rX@05 r");
+    let before = state.clone();
+    let undo = state.make(Action::from_notation("x2,1").unwrap());
+    assert_eq!(state.to_notation(), "r2@00,b2@30,bX@94,bX@95,rX@04,rX@05 b 1");
+    state.unmake(undo);
+    assert_eq!(state, before);
+    check_every_action(&mut state);
+}
+
+#[test]
+fn unmake_restores_mutual_kills() {
+    let mut state = position("r2@44,b2@45,r1@00,b1@99 r");
+    let before = state.clone();
+    let undo = state.make(Action::from_notation("4,4-4,5").unwrap());
+    assert_eq!(state.to_notation(), "r1@00,b1@99 b 1");
+    state.unmake(undo);
+    assert_eq!(state, before);
+}
+
+#[test]
+fn unmake_restores_a_b_hitting_two_pieces() {
+    let mut state = position("b1@46,rB4@44,b2@56,b1@99 r");
+    let before = state.clone();
+    let undo = state.make(Action::from_notation("4,4-4,5").unwrap());
+    assert_eq!(state.to_notation(), "rB2@45,b1@56,b1@99 b 1");
+    state.unmake(undo);
+    assert_eq!(state, before);
+    check_every_action(&mut state);
+}
+
+#[test]
+fn unmake_restores_a_line_of_actions_in_reverse() {
+    let mut state = GameState::new();
+    let mut positions = Vec::new();
+    let mut undos = Vec::new();
+    for action in ["x2,1", "8,0-7,0", "1,1-2,1", "x6,1", "0,3-2,3", "8,2-7,2"] {
+        let action = legal_action(&state, action);
+        positions.push(state.clone());
+        undos.push(state.make(action));
+    }
+    while let Some(undo) = undos.pop() {
+        state.unmake(undo);
+        assert_eq!(state, positions.pop().unwrap());
+    }
+}
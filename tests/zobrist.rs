@@ -0,0 +1,33 @@
+// The hash kept up to date by make and unmake has to match one worked out from scratch.
+
+mod common;
+
+use common::{check_every_action, legal_action, position};
+use epic_not_chess_game::GameState;
+
+#[test]
+fn hash_matches_along_a_line_with_explosions() {
+    let mut state = GameState::new();
+    let mut hashes = Vec::new();
+    let mut undos = Vec::new();
+    for action in ["x2,1", "8,0-7,0", "1,1-2,1", "x6,1", "0,3-2,3", "8,2-7,2", "x2,7", "x6,7"] {
+        check_every_action(&mut state);
+        let action = legal_action(&state, action);
+        hashes.push(state.hash());
+        undos.push(state.make(action));
+    }
+    check_every_action(&mut state);
+    while let Some(undo) = undos.pop() {
+        state.unmake(undo);
+        assert_eq!(state.hash(), hashes.pop().unwrap());
+    }
+}
+
+#[test]
+fn hash_depends_on_the_side_to_move() {
+    let red = position("r2@00,b2@99 r");
+    let blue = position("r2@00,b2@99 b");
+    assert_ne!(red.hash(), blue.hash());
+    // But not on the ply count
+    assert_eq!(red.hash(), position("r2@00,b2@99 r 12").hash());
+}